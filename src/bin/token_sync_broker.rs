@@ -0,0 +1,34 @@
+// src/bin/token_sync_broker.rs
+//
+// Standalone entry point for `TokenSyncBroker` (chunk1-1). Every fuzzer
+// process started by `Launcher` is a `TokenSyncClient`, publishing to and
+// pulling from a broker -- nothing in the fuzzer binary itself ever
+// constructs or serves one. Run this once per campaign, pointed at by every
+// node's `token_sync.broker_addr`, before starting the fuzzer processes.
+
+#[path = "../tokens/network_token_sync.rs"]
+mod network_token_sync;
+
+use network_token_sync::TokenSyncBroker;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let bind_addr = args.next().unwrap_or_else(|| {
+        eprintln!("Usage: token_sync_broker <bind_addr> [max_tokens] [max_token_length]");
+        std::process::exit(1);
+    });
+    let max_tokens: usize = args.next().and_then(|s| s.parse().ok()).unwrap_or(10_000);
+    let max_token_length: usize = args.next().and_then(|s| s.parse().ok()).unwrap_or(256);
+
+    let broker = TokenSyncBroker::bind(&bind_addr, max_tokens, max_token_length)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to bind {bind_addr}: {e}");
+            std::process::exit(1);
+        });
+
+    println!("token_sync_broker listening on {bind_addr} (max_tokens={max_tokens}, max_token_length={max_token_length})");
+    if let Err(e) = broker.serve() {
+        eprintln!("Broker loop exited: {e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,203 @@
+// src/cmp_observer.rs
+//
+// Comparison-operand logging for input-to-state (RedQueen-style) token
+// discovery: records the `(lhs, rhs)` operand pairs seen at comparison and
+// switch sites in the target during a single execution, so
+// `search_tokens_from_cmp` can derive tokens from "what value would flip
+// this branch" instead of only from the byte-flip + coverage-diff loop
+// `MutationDeltaExtractor` needs.
+//
+// Operand pairs are captured via `-fsanitize-coverage=trace-cmp` hooks, the
+// same SanitizerCoverage instrumentation `EDGES_MAP` relies on for edge
+// coverage; a single process-wide log mirrors that static-buffer approach,
+// since each `Launcher`-forked fuzzer process only ever runs one target
+// execution at a time.
+
+use std::borrow::Cow;
+use std::sync::Mutex;
+
+use libafl::executors::ExitKind;
+use libafl::{observers::Observer, Error};
+use libafl_bolts::Named;
+use serde::{Deserialize, Serialize};
+
+/// One side of a logged comparison. Target-side instrumentation widens
+/// everything to its natural integer width; `Bytes` covers `memcmp`/
+/// `strcmp`-style comparisons.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CmpOperand {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Bytes(Vec<u8>),
+}
+
+impl CmpOperand {
+    /// Byte encodings of this operand worth checking against an input:
+    /// little- and big-endian for integers, plus its decimal ASCII
+    /// rendering (comparisons against text-encoded numbers are common in
+    /// parsers). `Bytes` is already a concrete byte string and has one.
+    pub fn encodings(&self) -> Vec<Vec<u8>> {
+        match self {
+            CmpOperand::U8(v) => vec![vec![*v], v.to_string().into_bytes()],
+            CmpOperand::U16(v) => vec![
+                v.to_le_bytes().to_vec(),
+                v.to_be_bytes().to_vec(),
+                v.to_string().into_bytes(),
+            ],
+            CmpOperand::U32(v) => vec![
+                v.to_le_bytes().to_vec(),
+                v.to_be_bytes().to_vec(),
+                v.to_string().into_bytes(),
+            ],
+            CmpOperand::U64(v) => vec![
+                v.to_le_bytes().to_vec(),
+                v.to_be_bytes().to_vec(),
+                v.to_string().into_bytes(),
+            ],
+            CmpOperand::Bytes(b) => vec![b.clone()],
+        }
+    }
+}
+
+const CMP_LOG_CAPACITY: usize = 1024;
+
+// Process-wide scratch log the `__sanitizer_cov_trace_cmp*` hooks below
+// write into. There's no per-observer handle to record through from target
+// code, so (like `EDGES_MAP`) this is a single shared buffer that
+// `CmpMapObserver::post_exec` drains after every execution.
+static CMP_LOG: Mutex<Vec<(CmpOperand, CmpOperand)>> = Mutex::new(Vec::new());
+
+fn push_pair(lhs: CmpOperand, rhs: CmpOperand) {
+    if let Ok(mut log) = CMP_LOG.lock() {
+        if log.len() < CMP_LOG_CAPACITY {
+            log.push((lhs, rhs));
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __sanitizer_cov_trace_cmp1(arg1: u8, arg2: u8) {
+    push_pair(CmpOperand::U8(arg1), CmpOperand::U8(arg2));
+}
+
+#[no_mangle]
+pub extern "C" fn __sanitizer_cov_trace_cmp2(arg1: u16, arg2: u16) {
+    push_pair(CmpOperand::U16(arg1), CmpOperand::U16(arg2));
+}
+
+#[no_mangle]
+pub extern "C" fn __sanitizer_cov_trace_cmp4(arg1: u32, arg2: u32) {
+    push_pair(CmpOperand::U32(arg1), CmpOperand::U32(arg2));
+}
+
+#[no_mangle]
+pub extern "C" fn __sanitizer_cov_trace_cmp8(arg1: u64, arg2: u64) {
+    push_pair(CmpOperand::U64(arg1), CmpOperand::U64(arg2));
+}
+
+/// A log of comparison-operand pairs collected during one target execution.
+/// Abstracts over the observer that actually owns the log
+/// (`CmpMapObserver` below), the same way `MapObserver` abstracts over
+/// coverage-map storage.
+pub trait CmpMap {
+    fn pairs(&self) -> &[(CmpOperand, CmpOperand)];
+    fn clear(&mut self);
+}
+
+/// Owns the per-execution comparison log: clears the shared
+/// `__sanitizer_cov_trace_cmp*` buffer before each run and drains it into
+/// its own storage afterward, so downstream code sees a stable snapshot
+/// even though the next execution starts clearing the shared buffer again
+/// immediately.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmpMapObserver {
+    name: Cow<'static, str>,
+    log: Vec<(CmpOperand, CmpOperand)>,
+    capacity: usize,
+}
+
+impl CmpMapObserver {
+    #[must_use]
+    pub fn new(name: &'static str, capacity: usize) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            log: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+}
+
+impl CmpMap for CmpMapObserver {
+    fn pairs(&self) -> &[(CmpOperand, CmpOperand)] {
+        &self.log
+    }
+
+    fn clear(&mut self) {
+        self.log.clear();
+    }
+}
+
+impl Named for CmpMapObserver {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl<I, S> Observer<I, S> for CmpMapObserver {
+    fn pre_exec(&mut self, _state: &mut S, _input: &I) -> Result<(), Error> {
+        self.clear();
+        if let Ok(mut log) = CMP_LOG.lock() {
+            log.clear();
+        }
+        Ok(())
+    }
+
+    fn post_exec(&mut self, _state: &mut S, _input: &I, _exit_kind: &ExitKind) -> Result<(), Error> {
+        if let Ok(mut log) = CMP_LOG.lock() {
+            let take = log.len().min(self.capacity);
+            self.log = log.drain(..take).collect();
+        }
+        Ok(())
+    }
+}
+
+/// Input-to-state token discovery: given the comparison operand pairs
+/// logged by a `CmpMap` observer during the last execution of `input`, find
+/// pairs where one side's byte encoding is already present in `input` while
+/// the other side is a constant that is absent, and return that constant as
+/// a token along with the input offset it anchors to. This is the "what
+/// value would turn this branch into a straight jump" trick
+/// (RedQueen/input-to-state), and finds magic bytes in a single execution
+/// instead of the byte-flip-and-diff-coverage loop `MutationDeltaExtractor`
+/// needs.
+pub fn search_tokens_from_cmp(input: &[u8], pairs: &[(CmpOperand, CmpOperand)]) -> Vec<(Vec<u8>, usize)> {
+    pairs
+        .iter()
+        .filter_map(|(lhs, rhs)| {
+            constant_from_pair(input, lhs, rhs).or_else(|| constant_from_pair(input, rhs, lhs))
+        })
+        .collect()
+}
+
+/// If `present`'s byte encoding occurs verbatim in `input` while none of
+/// `constant`'s encodings do, returns `constant`'s first encoding as a
+/// token along with the offset where `present` matched (so the caller knows
+/// where the comparison anchors in the input).
+fn constant_from_pair(input: &[u8], present: &CmpOperand, constant: &CmpOperand) -> Option<(Vec<u8>, usize)> {
+    let present_offset = present.encodings().iter().find_map(|enc| find_subslice(input, enc))?;
+
+    let encodings = constant.encodings();
+    if encodings.iter().any(|enc| find_subslice(input, enc).is_some()) {
+        return None;
+    }
+    encodings.into_iter().next().map(|enc| (enc, present_offset))
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
@@ -3,6 +3,9 @@ use std::{fs, process};
 use std::sync::OnceLock;
 use serde_json::Value;
 
+use crate::tokens::network_token_sync::SyncMode;
+use crate::processors::{IntWidth, NgramSelectionMode};
+
 static CONFIG: OnceLock<TokenDiscoveryConfig> = OnceLock::new();
 
 #[derive(Deserialize, Debug, Clone, Copy, Default)]
@@ -14,6 +17,41 @@ pub enum FuzzerPreset {
     PreservingTokens,
 }
 
+/// How two raw coverage maps are compared when looking for a coverage-
+/// equivalent candidate (`MutationDeltaExtractor`'s ddmin). `Exact` is the
+/// historical behavior; `Bucketed` runs each entry through `classify_count`
+/// (AFL-style log-scale hit-count buckets) first so loop-iteration noise
+/// doesn't break equivalence; `TouchedEdges` reduces further to "was this
+/// edge hit at all".
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageComparisonMode {
+    #[default]
+    Exact,
+    Bucketed,
+    TouchedEdges,
+}
+
+/// How `CorpusExtractor` (and the suffix-array/n-gram discovery strategies)
+/// build their `search_pool_size`-capped working set out of the full corpus.
+/// `Recent` is the historical behavior and is biased toward whatever was
+/// added most recently, regardless of how interesting it turned out to be.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolSamplingStrategy {
+    #[default]
+    Recent,
+    /// Favor testcases the scheduler has picked most often, on the theory
+    /// that a frequently-scheduled input is one the scheduler considers
+    /// unusually productive.
+    Favored,
+    /// Bucket the corpus by scheduled-count into diversity strata and
+    /// sample evenly across them, so the pool spans both favored and
+    /// rarely-picked behavior rather than a single temporal or popularity
+    /// slice.
+    StratifiedRandom,
+}
+
 #[derive(Deserialize, Debug, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum SchedulerPreset {
@@ -62,6 +100,38 @@ impl ThresholdFunction {
 }
 
 
+fn default_sync_interval() -> u32 { 1 }
+fn default_dot_interval() -> u32 { 10 }
+
+/// Periodically dumps the token co-occurrence graph as a Graphviz `.dot`
+/// file, gated behind `TokenDiscoveryStage`'s search interval like the
+/// numeric stats are. Independent of the Prometheus/MultiMonitor reporting.
+#[derive(Deserialize, Debug, Clone)]
+pub struct DotExportConfig {
+    pub path: String,
+    // How many `TokenDiscoveryStage` runs between dumps.
+    #[serde(default = "default_dot_interval")]
+    pub interval: u32,
+}
+
+fn default_int_widths() -> Vec<IntWidth> {
+    vec![IntWidth::I32, IntWidth::U32, IntWidth::I64, IntWidth::U64]
+}
+
+fn default_ngram_min_count() -> usize { 2 }
+
+/// Cross-host token pooling over `broker_addr`, mirroring
+/// `SharedTokenStorage`'s local seqlock sharing but over TCP. Only present
+/// when the campaign is meant to pool tokens across machines.
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkTokenSyncConfig {
+    pub broker_addr: String,
+    pub mode: SyncMode,
+    // How many `search_interval`-driven discovery rounds between sync attempts.
+    #[serde(default = "default_sync_interval")]
+    pub sync_interval: u32,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ProcessorConfig {
@@ -74,8 +144,25 @@ pub enum ProcessorConfig {
     RemoveSimilar {
         threshold: f64,
         keep_longer: bool,
+        // MinHash/LSH pre-bucketing: only run the similarity check within
+        // tokens that collide in at least one band, instead of against
+        // every previously kept token. `bands * rows` is the MinHash
+        // signature length. Falls back to the exhaustive O(n^2) behavior
+        // when either is unset.
+        #[serde(default)]
+        bands: Option<usize>,
+        #[serde(default)]
+        rows: Option<usize>,
+        #[serde(default)]
+        shingle_size: Option<usize>,
     },
     RemoveSubstrings,
+    TypeClassify {
+        #[serde(default = "default_int_widths")]
+        widths: Vec<IntWidth>,
+        #[serde(default)]
+        timestamp_formats: Vec<String>,
+    },
     Sais {
         #[serde(default)]
         min_len: Option<usize>,
@@ -87,6 +174,41 @@ pub enum ProcessorConfig {
         token_count: Option<usize>,
         #[serde(default)]
         threshold_fn: Option<ThresholdFunction>,
+        #[serde(default)]
+        maximal_only: bool,
+        // Worker threads for the SA/PLCP/LCP construction. `1` (the default)
+        // keeps the original single-threaded libsais builders.
+        #[serde(default)]
+        threads: Option<usize>,
+        // When scanning the LCP array in parallel, let workers grab
+        // variable-sized batches of the remaining split points instead of a
+        // fixed per-worker share, to avoid tail stragglers on skewed corpora.
+        #[serde(default)]
+        dynamic_batch: bool,
+        // Greedily select up to this many candidates by corpus-byte savings
+        // rather than raw frequency. Takes priority over `threshold_fn` /
+        // `threshold` / `token_count` when set.
+        #[serde(default)]
+        max_compression_tokens: Option<usize>,
+        // Select up to this many candidates by TF-IDF-style rarity weight
+        // instead of raw frequency, favoring substrings that recur within a
+        // subset of the corpus over boilerplate common to nearly all of it.
+        // Takes priority over `max_compression_tokens` / `threshold_fn` /
+        // `threshold` / `token_count` when set.
+        #[serde(default)]
+        weighted_rarity_tokens: Option<usize>,
+    },
+    Ngram {
+        min_n: usize,
+        max_n: usize,
+        #[serde(default = "default_ngram_min_count")]
+        min_count: usize,
+        pmi_threshold: f64,
+        #[serde(default)]
+        selection_mode: NgramSelectionMode,
+        // Only used by `NgramSelectionMode::MinTokenCount`
+        #[serde(default)]
+        token_count: usize,
     },
     SplitAt {
         delimiters: Vec<Vec<u8>>,
@@ -98,6 +220,12 @@ pub enum ProcessorConfig {
         #[serde(default)]
         min_length: Option<usize>,
     },
+    TfIdfPrune {
+        #[serde(default)]
+        top_k: Option<usize>,
+        #[serde(default)]
+        min_score: Option<f64>,
+    },
 }
 
 #[derive(Deserialize, Debug)]
@@ -127,10 +255,58 @@ pub struct TokenDiscoveryConfig {
     pub min_token_length: usize,
     pub search_pool_size: usize,
     pub displayed_tokens: usize,
+    // How the discovery pool is drawn from the corpus. See `PoolSamplingStrategy`.
+    #[serde(default)]
+    pub pool_sampling: PoolSamplingStrategy,
 
     // Strategy config
     pub extractor: ExtractorConfig,
     pub pipeline: Vec<ProcessorConfig>,
+
+    // Distributed sync settings
+    #[serde(default)]
+    pub token_sync: Option<NetworkTokenSyncConfig>,
+
+    // Visualization settings
+    #[serde(default)]
+    pub dot_export: Option<DotExportConfig>,
+
+    // Dictionary persistence settings
+    // AFL/libFuzzer `.dict` path the learned `SmartTokens` set is seeded
+    // from on startup and periodically written back out to (reusing
+    // `search_interval`, same as `TokenDiscoveryStage`'s own gating).
+    #[serde(default)]
+    pub dict_path: Option<String>,
+
+    // Coverage comparison settings
+    // How raw coverage maps are compared when `MutationDeltaExtractor`'s
+    // ddmin looks for a coverage-equivalent candidate. See
+    // `CoverageComparisonMode`.
+    #[serde(default)]
+    pub coverage_comparison: CoverageComparisonMode,
+
+    // If set, re-execute the target this many extra times for both the
+    // baseline and each candidate and intersect the resulting coverage
+    // (via `intersect_coverage`) before comparing, filtering out edges that
+    // only toggle due to target-side flakiness rather than the candidate
+    // bytes themselves.
+    #[serde(default)]
+    pub coverage_stabilize_runs: Option<u32>,
+
+    // Token liveness settings
+    // Every token's liveness score is multiplied by this factor once per
+    // `TokenDiscoveryStage` cycle; a token whose score decays to zero is
+    // pruned from `SmartTokens` unless it's still within its probation
+    // window. Unset disables decay/pruning entirely, keeping the dictionary
+    // monotonically growing as before.
+    #[serde(default)]
+    pub token_decay_factor: Option<f64>,
+
+    // A token is exempt from pruning until it has accumulated at least this
+    // many uses since it was (re)inserted, giving freshly discovered tokens
+    // a grace window to prove themselves before they can be evicted.
+    #[serde(default)]
+    pub token_probation_uses: Option<u32>,
 }
 
 impl TokenDiscoveryConfig {
@@ -0,0 +1,146 @@
+// src/dict_format.rs
+//
+// Read/write support for the AFL/libFuzzer `.dict` text format: one
+// `name="\xAB\xCD..."` or bare `"\xAB\xCD..."` entry per line, blank lines
+// and `#` comments ignored, `@level` suffixes on the name (e.g.
+// `name@1="..."`) accepted and ignored on read, and a trailing `# comment`
+// after the closing quote tolerated for dictionaries authored by other
+// AFL++/honggfuzz/libFuzzer tooling. Also provides a binary companion
+// format (`write_dict_bin`/`parse_dict_bin`) for fast reload.
+
+/// Parses a `.dict` file's contents into raw token bytes.
+#[must_use]
+pub fn parse_dict(contents: &str) -> Vec<Vec<u8>> {
+    contents.lines().filter_map(parse_dict_line).collect()
+}
+
+fn parse_dict_line(line: &str) -> Option<Vec<u8>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    // Either `name@level="value"` or a bare `"value"`; the quoted value is
+    // always what follows the last `=` before the opening quote, so a bare
+    // line (no `=`) just uses the whole line.
+    let open = line.find('"')?;
+    let value = match line[..open].rfind('=') {
+        Some(eq) => line[eq + 1..].trim(),
+        None => line,
+    };
+    // The closing quote isn't required to be the line's last character --
+    // AFL++-authored dictionaries sometimes trail a `# comment` after it --
+    // so take up to the closing quote rather than requiring it at the end.
+    // An escaped `\"` inside the value doesn't count as the close.
+    let value = value.strip_prefix('"')?;
+    let end = find_closing_quote(value)?;
+    Some(unescape(&value[..end]))
+}
+
+/// Finds the byte offset of the first `"` in `value` that isn't escaped
+/// with a preceding `\`, i.e. the real end of a quoted dict value.
+fn find_closing_quote(value: &str) -> Option<usize> {
+    let bytes = value.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => return Some(i),
+            b'\\' if i + 1 < bytes.len() => i += 2,
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn unescape(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() && matches!(bytes[i + 1], b'x' | b'X') {
+            if let Ok(byte) = u8::from_str_radix(&value[i + 2..i + 4], 16) {
+                out.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        if bytes[i] == b'\\' && i + 1 < bytes.len() && bytes[i + 1] == b'\\' {
+            out.push(b'\\');
+            i += 2;
+            continue;
+        }
+        if bytes[i] == b'\\' && i + 1 < bytes.len() && bytes[i + 1] == b'"' {
+            out.push(b'"');
+            i += 2;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    out
+}
+
+/// Renders `tokens` as `.dict` text, escaping non-printable bytes as
+/// `\xHH`.
+#[must_use]
+pub fn write_dict(tokens: &[Vec<u8>]) -> String {
+    let mut out = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        out.push_str(&format!("tok_{i}=\"{}\"\n", escape(token)));
+    }
+    out
+}
+
+fn escape(token: &[u8]) -> String {
+    token
+        .iter()
+        .map(|&b| match b {
+            b'"' | b'\\' => format!("\\{}", b as char),
+            0x20..=0x7e => (b as char).to_string(),
+            _ => format!("\\x{b:02x}"),
+        })
+        .collect()
+}
+
+/// Companion binary format for `write_dict`/`parse_dict`: a `u32` token
+/// count followed by `(u32 length, bytes)` per token, all little-endian.
+/// Avoids re-parsing the escaped text grammar on every reload.
+#[must_use]
+pub fn write_dict_bin(tokens: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + tokens.iter().map(|t| 4 + t.len()).sum::<usize>());
+    out.extend_from_slice(&(tokens.len() as u32).to_le_bytes());
+    for token in tokens {
+        out.extend_from_slice(&(token.len() as u32).to_le_bytes());
+        out.extend_from_slice(token);
+    }
+    out
+}
+
+/// Inverse of `write_dict_bin`. Never errors: returns an empty `Vec` if
+/// `data` is too short to hold the count, and otherwise the tokens read
+/// before the first truncated entry, matching `parse_dict`'s best-effort
+/// handling of malformed input.
+#[must_use]
+pub fn parse_dict_bin(data: &[u8]) -> Vec<Vec<u8>> {
+    let Some(count) = data.get(..4).map(|b| u32::from_le_bytes(b.try_into().unwrap())) else {
+        return Vec::new();
+    };
+
+    let mut tokens = Vec::with_capacity(count as usize);
+    let mut pos = 4;
+    for _ in 0..count {
+        let Some(len) = data.get(pos..pos + 4).map(|b| u32::from_le_bytes(b.try_into().unwrap())) else {
+            break;
+        };
+        pos += 4;
+        let len = len as usize;
+        let Some(token) = data.get(pos..pos + len) else {
+            break;
+        };
+        tokens.push(token.to_vec());
+        pos += len;
+    }
+    tokens
+}
@@ -1,32 +1,20 @@
-use libafl::corpus::Corpus;
 use libafl::inputs::HasTargetBytes;
-use libafl::state::HasCorpus;
+use libafl::state::{HasCorpus, HasRand};
 
 use crate::config::config;
+use crate::extractors::pool_sampling::sample_pool;
 
 pub struct CorpusExtractor;
 
 impl CorpusExtractor {
-    pub fn extract<I, S>(&self, state: &S) -> Option<Vec<Vec<u8>>>
+    pub fn extract<I, S>(&self, state: &mut S) -> Option<Vec<Vec<u8>>>
     where
         I: HasTargetBytes + Clone,
-        S: HasCorpus<I>,
+        S: HasCorpus<I> + HasRand,
     {
         let cfg = config();
 
-        let corpus: Vec<Vec<u8>> = state
-            .corpus()
-            .ids()
-            .rev()
-            .take(cfg.search_pool_size)
-            .filter_map(|id| {
-                state
-                    .corpus()
-                    .cloned_input_for_id(id)
-                    .ok()
-                    .map(|input| input.target_bytes().to_vec())
-            })
-            .collect();
+        let corpus = sample_pool(state, cfg.pool_sampling, cfg.search_pool_size);
 
         if corpus.is_empty() { None } else { Some(corpus) }
     }
@@ -1,5 +1,6 @@
 mod corpus;
 mod mutation_delta;
+pub mod pool_sampling;
 
 pub use corpus::CorpusExtractor;
 pub use mutation_delta::MutationDeltaExtractor;
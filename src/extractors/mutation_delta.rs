@@ -1,4 +1,4 @@
-use std::cmp::min;
+use std::collections::{HashMap, HashSet};
 
 use libafl::corpus::Corpus;
 use libafl::events::EventFirer;
@@ -7,10 +7,10 @@ use libafl::inputs::HasTargetBytes;
 use libafl::observers::MapObserver;
 use libafl::state::{HasCorpus, HasCurrentTestcase, HasRand};
 use libafl::Error;
-use libafl_bolts::rands::Rand;
 use libafl_bolts::tuples::{Handle, Handled, MatchNameRef};
 
 use crate::config::config;
+use crate::utils::{coverage_matches, intersect_coverage};
 
 pub struct MutationDeltaExtractor<C> {
     observer_handle: Handle<C>,
@@ -36,6 +36,7 @@ impl<C> MutationDeltaExtractor<C> {
         S: HasCorpus<I> + HasCurrentTestcase<I> + HasRand,
         C: Handled + AsRef<O> + AsMut<O>,
         O: MapObserver,
+        O::Entry: Into<u64>,
     {
         let cfg = config();
 
@@ -53,79 +54,198 @@ impl<C> MutationDeltaExtractor<C> {
             original_input.target_bytes().to_vec()
         };
 
-        let mut test_vec = original_bytes.clone();
+        let mutated_map = self.get_stable_coverage(&mutated_bytes.clone().into(), fuzzer, executor, state, manager).ok()?;
 
-        let original_map = self.get_coverage(&original_bytes.clone().into(), fuzzer, executor, state, manager).ok()?;
-        let mutated_map = self.get_coverage(&mutated_bytes.clone().into(), fuzzer, executor, state, manager).ok()?;
+        // The "change set" ddmin minimizes over: every index where the
+        // mutated input differs from the original (or has no original byte
+        // at all, if the mutation extended the input).
+        let mut base = original_bytes.clone();
+        base.resize(mutated_bytes.len(), 0);
+        let change_set: Vec<usize> = (0..mutated_bytes.len())
+            .filter(|&i| i >= original_bytes.len() || mutated_bytes[i] != original_bytes[i])
+            .collect();
 
-        let mut left_bound = 0;
-        let mut right_bound = 0;
+        if change_set.is_empty() {
+            return None;
+        }
 
-        // Find right bound
-        for i in 0..mutated_bytes.len() {
-            if i >= test_vec.len() {
-                test_vec.push(mutated_bytes[i]);
-            } else {
-                test_vec[i] = mutated_bytes[i];
-            }
+        let mut cache: HashMap<Vec<bool>, bool> = HashMap::new();
+        let minimal = self.ddmin(
+            &change_set, &change_set, &base, &mutated_bytes, &mutated_map,
+            &mut cache, fuzzer, executor, state, manager,
+        )?;
 
-            let coverage = self.get_coverage(&test_vec.clone().into(), fuzzer, executor, state, manager).ok()?;
-            if coverage == mutated_map {
-                left_bound = i;
-                right_bound = i + 1;
-                break;
+        let left_bound = *minimal.iter().min().unwrap();
+        let right_bound = *minimal.iter().max().unwrap() + 1;
+        let token_length = right_bound - left_bound;
+
+        if token_length >= cfg.min_token_length {
+            if !cfg.silent_run  {
+                println!(
+                    "[{}] Found token of length {} at position {}",
+                    self.name(),
+                    token_length,
+                    left_bound
+                );
             }
+            Some(vec![mutated_bytes[left_bound..right_bound].to_vec()])
+        } else {
+            None
         }
+    }
 
-        // Extend right bound
-        for i in right_bound..min(mutated_bytes.len(), left_bound + cfg.max_token_length) {
-            if i >= test_vec.len() {
+    /// Delta-debugging (ddmin) minimization of `current` down to a
+    /// 1-minimal subset of `change_set` indices that still reproduces
+    /// `mutated_map` when applied over `base`; the subset need not be
+    /// contiguous (`extract` takes the span from its lowest to its highest
+    /// index). Follows Zeller's
+    /// classic ddmin: split into `granularity` contiguous chunks, try each
+    /// chunk alone ("reduce to subset", resets granularity to 2 on success),
+    /// then each chunk's complement ("reduce to complement", granularity
+    /// drops to `max(granularity - 1, 2)`), otherwise double the granularity
+    /// until it exceeds what remains.
+    #[allow(clippy::too_many_arguments)]
+    fn ddmin<E, EM, I, S, Z, O>(
+        &self,
+        current: &[usize],
+        change_set: &[usize],
+        base: &[u8],
+        mutated_bytes: &[u8],
+        mutated_map: &[u64],
+        cache: &mut HashMap<Vec<bool>, bool>,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+    ) -> Option<Vec<usize>>
+    where
+        E: Executor<EM, I, S, Z> + HasObservers,
+        E::Observers: MatchNameRef,
+        I: Clone + From<Vec<u8>>,
+        C: Handled + AsRef<O> + AsMut<O>,
+        O: MapObserver,
+        O::Entry: Into<u64>,
+    {
+        let mut current = current.to_vec();
+        let mut granularity = 2usize;
+
+        while current.len() >= 2 {
+            if granularity > current.len() {
                 break;
             }
 
-            let tmp = test_vec[i];
-            test_vec[i] = state.rand_mut().next() as u8;
-            let coverage = self.get_coverage(&test_vec.clone().into(), fuzzer, executor, state, manager).ok()?;
-            test_vec[i] = tmp;
+            let chunk_size = current.len().div_ceil(granularity);
+            let chunks: Vec<Vec<usize>> = current.chunks(chunk_size).map(<[usize]>::to_vec).collect();
+            let mut reduced = false;
 
-            if coverage == mutated_map {
-                right_bound = i;
+            for chunk in &chunks {
+                if self.reproduces(chunk, change_set, base, mutated_bytes, mutated_map, cache, fuzzer, executor, state, manager).ok()? {
+                    current = chunk.clone();
+                    granularity = 2;
+                    reduced = true;
+                    break;
+                }
+            }
+            if reduced {
+                continue;
+            }
+
+            for chunk in &chunks {
+                let excluded: HashSet<usize> = chunk.iter().copied().collect();
+                let complement: Vec<usize> = current.iter().copied().filter(|i| !excluded.contains(i)).collect();
+                if complement.is_empty() {
+                    continue;
+                }
+                if self.reproduces(&complement, change_set, base, mutated_bytes, mutated_map, cache, fuzzer, executor, state, manager).ok()? {
+                    current = complement;
+                    granularity = granularity.saturating_sub(1).max(2);
+                    reduced = true;
+                    break;
+                }
+            }
+            if reduced {
+                continue;
+            }
+
+            if granularity >= current.len() {
                 break;
             }
+            granularity = (granularity * 2).min(current.len());
         }
 
-        // Find left bound
-        for i in right_bound.saturating_sub(cfg.max_token_length)..right_bound {
-            let tmp = test_vec[i];
-            test_vec[i] = if i >= original_bytes.len() || test_vec[i] == original_bytes[i] {
-                state.rand_mut().next() as u8
-            } else {
-                original_bytes[i]
-            };
+        Some(current)
+    }
+
+    /// Applies `subset` of `change_set`'s indices over `base` (every other
+    /// index keeps its original byte) and checks whether the resulting
+    /// input's coverage matches `mutated_map`, caching the result by the
+    /// boolean mask over `change_set` so re-running an already-tried
+    /// candidate is just a lookup.
+    #[allow(clippy::too_many_arguments)]
+    fn reproduces<E, EM, I, S, Z, O>(
+        &self,
+        subset: &[usize],
+        change_set: &[usize],
+        base: &[u8],
+        mutated_bytes: &[u8],
+        mutated_map: &[u64],
+        cache: &mut HashMap<Vec<bool>, bool>,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+    ) -> Result<bool, Error>
+    where
+        E: Executor<EM, I, S, Z> + HasObservers,
+        E::Observers: MatchNameRef,
+        I: Clone + From<Vec<u8>>,
+        C: Handled + AsRef<O> + AsMut<O>,
+        O: MapObserver,
+        O::Entry: Into<u64>,
+    {
+        let subset_set: HashSet<usize> = subset.iter().copied().collect();
+        let mask: Vec<bool> = change_set.iter().map(|i| subset_set.contains(i)).collect();
 
-            let coverage = self.get_coverage(&test_vec.clone().into(), fuzzer, executor, state, manager).ok()?;
-            test_vec[i] = tmp;
+        if let Some(&hit) = cache.get(&mask) {
+            return Ok(hit);
+        }
 
-            if coverage == original_map {
-                left_bound = i;
-                break;
-            }
+        let mut candidate = base.to_vec();
+        for &i in subset {
+            candidate[i] = mutated_bytes[i];
         }
 
-        let token_length = right_bound - left_bound;
-        if token_length >= cfg.min_token_length {
-            if !cfg.silent_run  {
-                println!(
-                    "[{}] Found token of length {} at position {}",
-                    self.name(),
-                    token_length,
-                    left_bound
-                );
-            }
-            Some(vec![mutated_bytes[left_bound..right_bound].to_vec()])
-        } else {
-            None
+        let coverage = self.get_stable_coverage(&candidate.into(), fuzzer, executor, state, manager)?;
+        let result = coverage_matches(&coverage, mutated_map, config().coverage_comparison);
+        cache.insert(mask, result);
+        Ok(result)
+    }
+
+    /// `get_coverage`, optionally re-executed `coverage_stabilize_runs`
+    /// extra times and intersected (see `intersect_coverage`) to filter out
+    /// edges that only toggle due to target-side flakiness.
+    fn get_stable_coverage<E, EM, I, S, Z, O>(
+        &self,
+        input: &I,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut S,
+        manager: &mut EM,
+    ) -> Result<Vec<u64>, Error>
+    where
+        E: Executor<EM, I, S, Z> + HasObservers,
+        E::Observers: MatchNameRef,
+        I: Clone,
+        C: Handled + AsRef<O> + AsMut<O>,
+        O: MapObserver,
+        O::Entry: Into<u64>,
+    {
+        let extra_runs = config().coverage_stabilize_runs.unwrap_or(0);
+        let mut maps = vec![self.get_coverage(input, fuzzer, executor, state, manager)?];
+        for _ in 0..extra_runs {
+            maps.push(self.get_coverage(input, fuzzer, executor, state, manager)?);
         }
+        Ok(intersect_coverage(&maps))
     }
 
     fn get_coverage<E, EM, I, S, Z, O>(
@@ -135,12 +255,13 @@ impl<C> MutationDeltaExtractor<C> {
         executor: &mut E,
         state: &mut S,
         manager: &mut EM,
-    ) -> Result<Vec<O::Entry>, Error>
+    ) -> Result<Vec<u64>, Error>
     where
         E: Executor<EM, I, S, Z> + HasObservers,
         E::Observers: MatchNameRef,
         C: Handled + AsRef<O> + AsMut<O>,
         O: MapObserver,
+        O::Entry: Into<u64>,
     {
         {
             let mut observers = executor.observers_mut();
@@ -159,7 +280,7 @@ impl<C> MutationDeltaExtractor<C> {
             .ok_or_else(|| Error::key_not_found("MapObserver not found".to_string()))?
             .as_ref();
 
-        Ok(edge_observer.to_vec())
+        Ok(edge_observer.to_vec().into_iter().map(Into::into).collect())
     }
 
     pub fn name(&self) -> &'static str { "mutation_delta" }
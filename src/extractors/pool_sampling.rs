@@ -0,0 +1,111 @@
+use std::num::NonZero;
+
+use libafl::corpus::{Corpus, CorpusId};
+use libafl::inputs::HasTargetBytes;
+use libafl::state::{HasCorpus, HasRand};
+
+pub use crate::config::PoolSamplingStrategy;
+
+/// Builds the `pool_size`-capped discovery working set out of the full
+/// corpus, using `strategy` to decide which testcases make the cut.
+pub fn sample_pool<I, S>(state: &mut S, strategy: PoolSamplingStrategy, pool_size: usize) -> Vec<Vec<u8>>
+where
+    I: HasTargetBytes + Clone,
+    S: HasCorpus<I> + HasRand,
+{
+    let ids: Vec<CorpusId> = state.corpus().ids().collect();
+    let selected = match strategy {
+        PoolSamplingStrategy::Recent => ids.into_iter().rev().take(pool_size).collect(),
+        PoolSamplingStrategy::Favored => favored_ids(state, ids, pool_size),
+        PoolSamplingStrategy::StratifiedRandom => stratified_ids(state, ids, pool_size),
+    };
+
+    selected
+        .into_iter()
+        .filter_map(|id: CorpusId| {
+            state
+                .corpus()
+                .cloned_input_for_id(id)
+                .ok()
+                .map(|input| input.target_bytes().to_vec())
+        })
+        .collect()
+}
+
+fn scheduled_count<I, S>(state: &S, id: CorpusId) -> usize
+where
+    S: HasCorpus<I>,
+{
+    state
+        .corpus()
+        .get(id)
+        .ok()
+        .map(|tc| tc.borrow().scheduled_count())
+        .unwrap_or(0)
+}
+
+/// Keeps the `pool_size` testcases the scheduler has picked most often,
+/// under the theory that a frequently-scheduled input is one the scheduler
+/// has found unusually productive.
+fn favored_ids<I, S>(state: &S, mut ids: Vec<CorpusId>, pool_size: usize) -> Vec<CorpusId>
+where
+    S: HasCorpus<I>,
+{
+    ids.sort_by_key(|&id| std::cmp::Reverse(scheduled_count(state, id)));
+    ids.truncate(pool_size);
+    ids
+}
+
+/// Number of scheduled-count strata to spread the pool across. Kept small
+/// and fixed since the pool itself is already bounded by `search_pool_size`.
+const STRATA_COUNT: usize = 4;
+
+/// Deals the corpus (sorted by scheduled-count) round-robin into
+/// `STRATA_COUNT` buckets -- so each bucket holds a spread of
+/// rarely-to-often-picked testcases rather than one contiguous tier --
+/// shuffles each bucket, then samples evenly across buckets. This keeps the
+/// pool from being dominated by whichever tier happens to be largest while
+/// still mixing in diversity from every tier.
+fn stratified_ids<I, S>(state: &mut S, mut ids: Vec<CorpusId>, pool_size: usize) -> Vec<CorpusId>
+where
+    S: HasCorpus<I> + HasRand,
+{
+    if ids.is_empty() || pool_size == 0 {
+        return Vec::new();
+    }
+
+    ids.sort_by_key(|&id| scheduled_count(state, id));
+
+    let num_strata = STRATA_COUNT.min(ids.len());
+    let mut strata: Vec<Vec<CorpusId>> = vec![Vec::new(); num_strata];
+    for (i, id) in ids.into_iter().enumerate() {
+        strata[i % num_strata].push(id);
+    }
+
+    for bucket in &mut strata {
+        for i in (1..bucket.len()).rev() {
+            let j = state.rand_mut().below(NonZero::new(i + 1).unwrap());
+            bucket.swap(i, j);
+        }
+    }
+
+    let mut selected = Vec::with_capacity(pool_size);
+    let mut cursor = 0;
+    while selected.len() < pool_size {
+        let before = selected.len();
+        for bucket in &mut strata {
+            if let Some(&id) = bucket.get(cursor) {
+                selected.push(id);
+                if selected.len() == pool_size {
+                    break;
+                }
+            }
+        }
+        if selected.len() == before {
+            break;
+        }
+        cursor += 1;
+    }
+
+    selected
+}
@@ -1,12 +1,15 @@
 use core::time::Duration;
 use std::{env, path::PathBuf};
 mod utils;
+mod cmp_observer;
 mod config;
+mod dict_format;
 mod smart_token_mutations;
 mod extractors;
 mod token_discovery_stage;
 mod token_preserving_scheduled_mutator;
 mod processors;
+mod tokens;
 
 use libafl::{
     corpus::{Corpus, InMemoryCorpus, /*InMemoryOnDiskCorpus,*/ OnDiskCorpus},
@@ -38,6 +41,7 @@ use libafl_bolts::{
 use libafl_targets::{libfuzzer_initialize, libfuzzer_test_one_input, EDGES_MAP, MAX_EDGES_FOUND};
 use mimalloc::MiMalloc;
 
+use crate::cmp_observer::CmpMapObserver;
 use crate::config::{config, ExtractorConfig, FuzzerPreset, SchedulerPreset};
 use crate::extractors::{Extractor, CorpusExtractor, MutationDeltaExtractor};
 use crate::processors::build_pipeline;
@@ -101,6 +105,8 @@ fn fuzz(corpus_dirs: &[PathBuf], objective_dir: PathBuf, broker_port: u16) -> Re
             let edges_observer = edges_observer.track_indices();
             let edges_handle = edges_observer.handle();
             let time_observer = TimeObserver::new("time");
+            let cmp_observer = CmpMapObserver::new("cmplog", 1024);
+            let cmp_handle = cmp_observer.handle();
 
             let map_feedback = MaxMapFeedback::new(&edges_observer);
             let calibration = CalibrationStage::new(&map_feedback);
@@ -120,7 +126,21 @@ fn fuzz(corpus_dirs: &[PathBuf], objective_dir: PathBuf, broker_port: u16) -> Re
             });
 
             if state.metadata_map().get::<SmartTokens>().is_none() {
-                state.add_metadata(SmartTokens::new());
+                state.add_metadata(SmartTokens::with_capacity(cfg.max_tokens));
+            }
+
+            // Seed the learned dictionary from a prior campaign, if configured,
+            // before we load the initial corpus.
+            if let Some(path) = &cfg.dict_path {
+                if let Ok(contents) = std::fs::read_to_string(path) {
+                    let seeded = crate::dict_format::parse_dict(&contents);
+                    if !seeded.is_empty() {
+                        if let Ok(token_meta) = state.metadata_mut::<SmartTokens>() {
+                            println!("Seeded {} tokens from {path}", seeded.len());
+                            token_meta.add_tokens(&seeded);
+                        }
+                    }
+                }
             }
 
             let power = match cfg.scheduler_preset {
@@ -150,7 +170,7 @@ fn fuzz(corpus_dirs: &[PathBuf], objective_dir: PathBuf, broker_port: u16) -> Re
 
             let mut executor = InProcessExecutor::with_timeout(
                 &mut harness,
-                tuple_list!(edges_observer, time_observer),
+                tuple_list!(edges_observer, time_observer, cmp_observer),
                 &mut fuzzer,
                 &mut state,
                 &mut restarting_mgr,
@@ -191,7 +211,7 @@ fn fuzz(corpus_dirs: &[PathBuf], objective_dir: PathBuf, broker_port: u16) -> Re
                         ),
                     };
                     let processors = build_pipeline(&cfg.pipeline);
-                    let discovery = TokenDiscoveryStage::new(extractor, processors);
+                    let discovery = TokenDiscoveryStage::new(extractor, processors, cmp_handle.clone());
 
                     let mut stages = tuple_list!(calibration, mutational, discovery);
                     fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut restarting_mgr)?;
@@ -212,7 +232,7 @@ fn fuzz(corpus_dirs: &[PathBuf], objective_dir: PathBuf, broker_port: u16) -> Re
                         ),
                     };
                     let processors = build_pipeline(&cfg.pipeline);
-                    let discovery = TokenDiscoveryStage::new(extractor, processors);
+                    let discovery = TokenDiscoveryStage::new(extractor, processors, cmp_handle.clone());
 
                     let mut stages = tuple_list!(calibration, mutational, discovery);
                     fuzzer.fuzz_loop_for(&mut stages, &mut executor, &mut state, &mut restarting_mgr, 10_000_000)?;
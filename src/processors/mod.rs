@@ -1,18 +1,24 @@
 pub mod sais;
+pub mod type_classify;
 mod filter_null_bytes;
 mod strip_bytes;
 mod remove_substrings;
 mod remove_similar;
 mod remove_repetitive;
 mod split_at;
+mod tf_idf_prune;
+mod ngram;
 
 pub use sais::{Sais, SelectionMode};
+pub use type_classify::{TypeClassify, IntWidth};
 pub use filter_null_bytes::FilterNullBytes;
 pub use strip_bytes::StripBytes;
 pub use remove_substrings::RemoveSubstrings;
 pub use remove_similar::{RemoveSimilar, KeepStrategy};
 pub use remove_repetitive::RemoveRepetitive;
 pub use split_at::SplitAt;
+pub use tf_idf_prune::TfIdfPrune;
+pub use ngram::{Ngram, NgramSelectionMode};
 
 use crate::config::{config, ProcessorConfig};
 
@@ -30,24 +36,52 @@ pub fn build_pipeline(configs: &[ProcessorConfig]) -> Vec<Box<dyn Processor>> {
             ProcessorConfig::RemoveRepetitive { threshold } => {
                 Box::new(RemoveRepetitive { threshold: *threshold })
             }
-            ProcessorConfig::RemoveSimilar { threshold, keep_longer } => {
+            ProcessorConfig::RemoveSimilar { threshold, keep_longer, bands, rows, shingle_size } => {
                 let keep = if *keep_longer { KeepStrategy::Longer } else { KeepStrategy::Shorter };
-                Box::new(RemoveSimilar { threshold: *threshold, keep })
+                Box::new(RemoveSimilar {
+                    threshold: *threshold,
+                    keep,
+                    bands: *bands,
+                    rows: *rows,
+                    shingle_size: *shingle_size,
+                })
             }
             ProcessorConfig::RemoveSubstrings => {
                 Box::new(RemoveSubstrings)
             }
-            ProcessorConfig::Sais { min_len, max_len, threshold, token_count, threshold_fn } => {
+            ProcessorConfig::TypeClassify { widths, timestamp_formats } => {
+                Box::new(TypeClassify { widths: widths.clone(), timestamp_formats: timestamp_formats.clone() })
+            }
+            ProcessorConfig::Sais { min_len, max_len, threshold, token_count, threshold_fn, maximal_only, threads, dynamic_batch, max_compression_tokens, weighted_rarity_tokens } => {
                 let cfg = config();
                 let min = min_len.unwrap_or(cfg.min_token_length);
                 let max = max_len.unwrap_or(cfg.max_token_length);
-                let mode = match (threshold_fn, threshold, token_count) {
-                    (Some(f), _, _) => SelectionMode::ThresholdFn(f.clone()),
-                    (_, Some(t), _) => SelectionMode::Threshold(*t),
-                    (_, _, Some(n)) => SelectionMode::MinTokenCount(*n),
+                let mode = match (weighted_rarity_tokens, max_compression_tokens, threshold_fn, threshold, token_count) {
+                    (Some(n), _, _, _, _) => SelectionMode::WeightedRarity { max_tokens: *n },
+                    (_, Some(n), _, _, _) => SelectionMode::MaxCompression { max_tokens: *n },
+                    (_, _, Some(f), _, _) => SelectionMode::ThresholdFn(f.clone()),
+                    (_, _, _, Some(t), _) => SelectionMode::Threshold(*t),
+                    (_, _, _, _, Some(n)) => SelectionMode::MinTokenCount(*n),
                     _ => SelectionMode::Threshold(0.3),
                 };
-                Box::new(Sais { min_len: min, max_len: max, mode })
+                Box::new(Sais {
+                    min_len: min,
+                    max_len: max,
+                    mode,
+                    maximal_only: *maximal_only,
+                    threads: threads.unwrap_or(1),
+                    dynamic_batch: *dynamic_batch,
+                })
+            }
+            ProcessorConfig::Ngram { min_n, max_n, min_count, pmi_threshold, selection_mode, token_count } => {
+                Box::new(Ngram {
+                    min_n: *min_n,
+                    max_n: *max_n,
+                    min_count: *min_count,
+                    pmi_threshold: *pmi_threshold,
+                    selection_mode: *selection_mode,
+                    token_count: *token_count,
+                })
             }
             ProcessorConfig::SplitAt { delimiters, min_length } => {
                 Box::new(SplitAt {
@@ -61,6 +95,9 @@ pub fn build_pipeline(configs: &[ProcessorConfig]) -> Vec<Box<dyn Processor>> {
                     min_length: min_length.unwrap_or(config().min_token_length)
                 })
             }
+            ProcessorConfig::TfIdfPrune { top_k, min_score } => {
+                Box::new(TfIdfPrune { top_k: *top_k, min_score: *min_score })
+            }
         }
     }).collect()
 }
\ No newline at end of file
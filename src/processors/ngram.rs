@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use super::Processor;
+
+fn default_min_count() -> usize { 2 }
+
+#[derive(serde::Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum NgramSelectionMode {
+    #[default]
+    PmiThreshold,
+    MinTokenCount,
+}
+
+/// Statistical frequency-based token discovery: rather than relying on
+/// cross-input suffix-array matches, scores every byte n-gram for `n` in
+/// `max(min_n, 2)..=max_n` (unigrams have no prefix to score against, so
+/// they are never emitted) by how much more often it appears than independence
+/// would predict — observed frequency vs. the product of its `(n-1)`-gram
+/// prefix's and unigram suffix's frequencies, PMI-style. This surfaces
+/// "surprisingly cohesive" sequences (magic numbers, keywords) that appear
+/// in only a few inputs but are highly non-random, which pure cross-input
+/// matching misses.
+pub struct Ngram {
+    pub min_n: usize,
+    pub max_n: usize,
+    pub min_count: usize,
+    pub pmi_threshold: f64,
+    pub selection_mode: NgramSelectionMode,
+    // Only used by `NgramSelectionMode::MinTokenCount`
+    pub token_count: usize,
+}
+
+impl Processor for Ngram {
+    fn process(&self, inputs: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+        self.score_ngrams(&inputs)
+    }
+
+    fn name(&self) -> &'static str { "ngram" }
+}
+
+impl Ngram {
+    /// Counts every n-gram for `n` in `1..=max_n` (so each scored gram's
+    /// `(n-1)`-gram prefix and unigram suffix counts are on hand), then
+    /// keeps grams whose observed/expected ratio clears `pmi_threshold`.
+    fn score_ngrams(&self, corpus: &[Vec<u8>]) -> Option<Vec<Vec<u8>>> {
+        let mut counts: Vec<HashMap<&[u8], usize>> = vec![HashMap::new(); self.max_n + 1];
+        let mut totals: Vec<usize> = vec![0; self.max_n + 1];
+
+        for entry in corpus {
+            for n in 1..=self.max_n {
+                if entry.len() < n {
+                    continue;
+                }
+                for window in entry.windows(n) {
+                    *counts[n].entry(window).or_insert(0) += 1;
+                    totals[n] += 1;
+                }
+            }
+        }
+
+        let mut candidates: Vec<(Vec<u8>, f64)> = Vec::new();
+        let total_unigrams = totals[1] as f64;
+
+        for n in self.min_n.max(2)..=self.max_n {
+            let total_n = totals[n] as f64;
+            let total_prefix = totals[n - 1] as f64;
+            if total_n == 0.0 || total_prefix == 0.0 || total_unigrams == 0.0 {
+                continue;
+            }
+
+            for (&gram, &count) in &counts[n] {
+                if count < self.min_count {
+                    continue;
+                }
+
+                let prefix_count = *counts[n - 1].get(&gram[..n - 1]).unwrap_or(&0);
+                let suffix_count = *counts[1].get(&gram[n - 1..]).unwrap_or(&0);
+                if prefix_count == 0 || suffix_count == 0 {
+                    continue;
+                }
+
+                let observed = count as f64 / total_n;
+                let expected = (prefix_count as f64 / total_prefix) * (suffix_count as f64 / total_unigrams);
+                if expected <= 0.0 {
+                    continue;
+                }
+
+                let ratio = observed / expected;
+                if ratio >= self.pmi_threshold {
+                    candidates.push((gram.to_vec(), ratio));
+                }
+            }
+        }
+
+        if let NgramSelectionMode::MinTokenCount = self.selection_mode {
+            candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            candidates.truncate(self.token_count);
+        }
+
+        let tokens: Vec<Vec<u8>> = candidates.into_iter().map(|(gram, _)| gram).collect();
+        if tokens.is_empty() { None } else { Some(tokens) }
+    }
+}
@@ -1,5 +1,7 @@
 // src/processors/similarity_filter.rs
 
+use std::collections::HashMap;
+
 use crate::config::config;
 use crate::print_stats;
 use super::Processor;
@@ -13,34 +15,158 @@ pub enum KeepStrategy {
 pub struct RemoveSimilar {
     pub threshold: f64,      // e.g., 0.9 = 90% similar triggers removal
     pub keep: KeepStrategy,
+    // MinHash/LSH pre-bucketing (see `lsh_filter`): only meaningful when
+    // both are set, otherwise every candidate is compared against every
+    // kept token as before.
+    pub bands: Option<usize>,
+    pub rows: Option<usize>,
+    pub shingle_size: Option<usize>,
 }
 
 impl RemoveSimilar {
-    fn levenshtein(a: &[u8], b: &[u8]) -> usize {
-        let mut prev: Vec<usize> = (0..=b.len()).collect();
-        let mut curr = vec![0; b.len() + 1];
-
-        for (i, &ca) in a.iter().enumerate() {
-            curr[0] = i + 1;
-            for (j, &cb) in b.iter().enumerate() {
-                curr[j + 1] = if ca == cb {
-                    prev[j]
+    /// Whether `a` and `b` are similar at or above `threshold`, without
+    /// computing a full O(|a|·|b|) edit distance. The maximum edit distance
+    /// that still clears the threshold is `k = floor((1 - threshold) *
+    /// max(|a|, |b|))`; a length gap wider than `k` disqualifies the pair
+    /// immediately, and otherwise a banded (Ukkonen) DP only fills the
+    /// diagonal band of width `2k+1`, aborting as soon as a completed row's
+    /// minimum exceeds `k` (no cell in a later row could recover from that).
+    fn is_similar(a: &[u8], b: &[u8], threshold: f64) -> bool {
+        let max_len = a.len().max(b.len());
+        if max_len == 0 {
+            return true;
+        }
+
+        let k = ((1.0 - threshold) * max_len as f64).floor() as usize;
+        if a.len().abs_diff(b.len()) > k {
+            return false;
+        }
+
+        Self::banded_distance(a, b, k).is_some()
+    }
+
+    /// Banded edit distance between `a` and `b`, capped at `k`: returns
+    /// `None` as soon as it's established the true distance exceeds `k`,
+    /// `Some(distance)` (always `<= k`) otherwise.
+    fn banded_distance(a: &[u8], b: &[u8], k: usize) -> Option<usize> {
+        let n = a.len();
+        let m = b.len();
+        let sentinel = k + 1;
+
+        let mut prev = vec![sentinel; m + 1];
+        for (j, slot) in prev.iter_mut().enumerate().take(m.min(k) + 1) {
+            *slot = j;
+        }
+
+        for i in 1..=n {
+            let lo = i.saturating_sub(k);
+            let hi = (i + k).min(m);
+            let mut curr = vec![sentinel; m + 1];
+            let mut row_min = sentinel;
+
+            for j in lo..=hi {
+                curr[j] = if j == 0 {
+                    i
                 } else {
-                    1 + prev[j].min(prev[j + 1]).min(curr[j])
+                    let cost = usize::from(a[i - 1] != b[j - 1]);
+                    let deletion = prev[j].saturating_add(1);
+                    let insertion = curr[j - 1].saturating_add(1);
+                    let substitution = prev[j - 1].saturating_add(cost);
+                    deletion.min(insertion).min(substitution)
                 };
+                row_min = row_min.min(curr[j]);
             }
-            std::mem::swap(&mut prev, &mut curr);
+
+            if row_min > k {
+                return None;
+            }
+            prev = curr;
         }
-        prev[b.len()]
+
+        (prev[m] <= k).then_some(prev[m])
     }
 
-    fn similarity(a: &[u8], b: &[u8]) -> f64 {
-        let max_len = a.len().max(b.len());
-        if max_len == 0 {
-            return 1.0;
+    fn shingles(token: &[u8], shingle_size: usize) -> Vec<&[u8]> {
+        if token.len() < shingle_size {
+            vec![token]
+        } else {
+            token.windows(shingle_size).collect()
+        }
+    }
+
+    /// FNV-1a over `bytes`, seeded so a single token's shingles produce a
+    /// different hash per MinHash seed.
+    fn seeded_hash(seed: u64, bytes: &[u8]) -> u64 {
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325 ^ seed;
+        for &b in bytes {
+            h ^= u64::from(b);
+            h = h.wrapping_mul(0x0000_0100_0000_01b3);
         }
-        let dist = Self::levenshtein(a, b);
-        1.0 - (dist as f64 / max_len as f64)
+        h
+    }
+
+    /// MinHash signature: one entry per seed, each the minimum seeded hash
+    /// over all of `token`'s `shingle_size`-byte shingles. Two tokens that
+    /// share many shingles are likely to agree on many signature entries.
+    fn minhash_signature(token: &[u8], seeds: &[u64], shingle_size: usize) -> Vec<u64> {
+        let shingles = Self::shingles(token, shingle_size);
+        seeds.iter()
+            .map(|&seed| shingles.iter().map(|s| Self::seeded_hash(seed, s)).min().unwrap_or(0))
+            .collect()
+    }
+
+    /// Hashes one band (`rows` consecutive signature entries) of `signature`
+    /// down to a single bucket key.
+    fn band_key(signature: &[u64], band: usize, rows: usize) -> u64 {
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+        for &v in &signature[band * rows..band * rows + rows] {
+            h ^= v;
+            h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        h
+    }
+
+    /// Greedy dominance filtering backed by banded LSH: a candidate is only
+    /// compared against previously kept tokens that collide with it in at
+    /// least one band, instead of against all of them. `bands * rows` is
+    /// the MinHash signature length (`m`); splitting it into `bands` bands
+    /// of `rows` rows means two tokens need only agree on one whole band to
+    /// become comparison candidates, trading a small false-negative rate
+    /// for avoiding the full O(n^2) pairwise scan.
+    fn lsh_filter(&self, sorted: Vec<Vec<u8>>, bands: usize, rows: usize, shingle_size: usize) -> Vec<Vec<u8>> {
+        let signature_len = bands * rows;
+        let seeds: Vec<u64> = (0..signature_len as u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15).wrapping_add(1))
+            .collect();
+
+        let mut result: Vec<Vec<u8>> = Vec::new();
+        let mut buckets: Vec<HashMap<u64, Vec<usize>>> = vec![HashMap::new(); bands];
+
+        for token in sorted {
+            let signature = Self::minhash_signature(&token, &seeds, shingle_size);
+            let band_keys: Vec<u64> = (0..bands).map(|band| Self::band_key(&signature, band, rows)).collect();
+
+            let mut candidates: Vec<usize> = Vec::new();
+            for (band, &key) in band_keys.iter().enumerate() {
+                if let Some(indices) = buckets[band].get(&key) {
+                    candidates.extend(indices.iter().copied());
+                }
+            }
+            candidates.sort_unstable();
+            candidates.dedup();
+
+            let dominated = candidates.iter().any(|&idx| Self::is_similar(&token, &result[idx], self.threshold));
+
+            if !dominated {
+                let idx = result.len();
+                for (band, &key) in band_keys.iter().enumerate() {
+                    buckets[band].entry(key).or_default().push(idx);
+                }
+                result.push(token);
+            }
+        }
+
+        result
     }
 }
 
@@ -55,17 +181,26 @@ impl Processor for RemoveSimilar {
             KeepStrategy::Shorter => sorted.sort_by(|a, b| a.len().cmp(&b.len())),
         }
 
-        let mut result: Vec<Vec<u8>> = Vec::new();
-
-        for token in sorted {
-            let dominated = result.iter().any(|existing| {
-                Self::similarity(&token, existing) >= self.threshold
-            });
+        let result = match (self.bands, self.rows) {
+            (Some(bands), Some(rows)) if bands > 0 && rows > 0 => {
+                let shingle_size = self.shingle_size.unwrap_or(4);
+                self.lsh_filter(sorted, bands, rows, shingle_size)
+            }
+            _ => {
+                let mut result: Vec<Vec<u8>> = Vec::new();
+                for token in sorted {
+                    let dominated = result.iter().any(|existing| {
+                        Self::is_similar(&token, existing, self.threshold)
+                    });
 
-            if !dominated {
-                result.push(token);
+                    if !dominated {
+                        result.push(token);
+                    }
+                }
+                result
             }
-        }
+        };
+
         if !config().silent_run {
             print_stats!(self.name(), "Removed {} similar tokens (threshold {:.0}%).",
                 original_count - result.len(),
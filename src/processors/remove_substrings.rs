@@ -1,3 +1,5 @@
+use aho_corasick::AhoCorasick;
+
 use crate::config::config;
 use crate::print_stats;
 use super::Processor;
@@ -10,17 +12,47 @@ impl Processor for RemoveSubstrings {
             return None;
         }
 
-        let mut sorted = inputs;
-        sorted.sort_by(|a, b| b.len().cmp(&a.len()));
+        // Every token is both a pattern and a segment of one shared haystack,
+        // so a single Aho-Corasick pass over the concatenation finds every
+        // token-in-token containment at once instead of re-scanning the
+        // growing retained set for each candidate (the old quadratic `windows`
+        // check).
+        let mut offsets = Vec::with_capacity(inputs.len() + 1);
+        let mut haystack = Vec::new();
+        offsets.push(0);
+        for token in &inputs {
+            haystack.extend_from_slice(token);
+            offsets.push(haystack.len());
+        }
+
+        let ac = AhoCorasick::new(inputs.iter().map(Vec::as_slice)).expect("token patterns");
+
+        // `hosts[i]` lists every token index whose span in `haystack` fully
+        // contains token `i` (excluding `i` itself).
+        let mut hosts: Vec<Vec<usize>> = vec![Vec::new(); inputs.len()];
+        for m in ac.find_overlapping_iter(&haystack) {
+            let pattern_idx = m.pattern().as_usize();
+            let (start, end) = (m.start(), m.end());
+            let seg = offsets.partition_point(|&o| o <= start).saturating_sub(1);
+            if seg != pattern_idx && start >= offsets[seg] && end <= offsets[seg + 1] {
+                hosts[pattern_idx].push(seg);
+            }
+        }
+
+        let mut order: Vec<usize> = (0..inputs.len()).collect();
+        order.sort_by(|&a, &b| inputs[b].len().cmp(&inputs[a].len()));
 
+        let mut inputs = inputs;
+        let mut kept = vec![false; order.len()];
         let mut result: Vec<Vec<u8>> = Vec::new();
-        for token in sorted {
-            let is_substring = result.iter()
-                .any(|existing| existing.windows(token.len()).any(|w| w == token.as_slice()));
-            if !is_substring {
-                result.push(token);
+        for idx in order {
+            let contained = hosts[idx].iter().any(|&h| kept[h]);
+            if !contained {
+                kept[idx] = true;
+                result.push(std::mem::take(&mut inputs[idx]));
             }
         }
+
         if !config().silent_run {
             print_stats!(self.name(), "Removed {} substrings from tokens.", token_len - result.len());
         }
@@ -1,4 +1,5 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 use libsais::SuffixArrayConstruction;
 use crate::print_stats;
@@ -10,12 +11,83 @@ pub enum SelectionMode {
     Threshold(f64),
     ThresholdFn(ThresholdFunction),
     MinTokenCount(usize),
+    /// Greedily select up to `max_tokens` candidates by how many corpus bytes
+    /// they'd save if used as a dictionary entry, rather than raw frequency.
+    MaxCompression { max_tokens: usize },
+    /// Select the top `max_tokens` candidates by [`Sais::rarity_weight`],
+    /// favoring substrings that recur consistently within a subset of the
+    /// corpus over ones that are simply frequent everywhere (boilerplate,
+    /// length prefixes).
+    WeightedRarity { max_tokens: usize },
+}
+
+/// A max-heap entry keyed by a `f64` utility score (never NaN in practice
+/// here), used by `Sais::select_by_compression`.
+struct HeapEntry(f64, usize);
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+}
+impl Eq for HeapEntry {}
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.0.total_cmp(&other.0) }
+}
+
+/// A minimal Fenwick (binary-indexed) tree over `0..len`, used to answer the
+/// offline "how many activated positions fall in `[l, r]`" range-sum queries
+/// in `Sais::scan_lcp_range`.
+struct Fenwick {
+    tree: Vec<usize>,
+}
+
+impl Fenwick {
+    fn new(len: usize) -> Self {
+        Fenwick { tree: vec![0; len + 1] }
+    }
+
+    fn add(&mut self, idx: usize, delta: usize) {
+        let mut i = idx + 1;
+        while i < self.tree.len() {
+            self.tree[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    fn prefix_sum(&self, idx: usize) -> usize {
+        let mut sum = 0;
+        let mut i = idx + 1;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+
+    fn range_sum(&self, l: usize, r: usize) -> usize {
+        self.prefix_sum(r) - if l > 0 { self.prefix_sum(l - 1) } else { 0 }
+    }
 }
 
 pub struct Sais {
     pub min_len: usize,
     pub max_len: usize,
     pub mode: SelectionMode,
+    // When true, only emit maximal repeats: an LCP-interval group is
+    // dropped unless the characters immediately preceding its occurrences
+    // differ (or one occurrence starts at position 0), so left/right
+    // extensions of the same repeat don't all get emitted as separate,
+    // mostly-redundant candidates.
+    pub maximal_only: bool,
+    // Number of worker threads for the SA/PLCP/LCP construction and the
+    // LCP-interval scan. `1` keeps the original single-threaded behavior.
+    pub threads: usize,
+    // When scanning the LCP array in parallel, let workers grab variable-sized
+    // batches of the remaining split points based on what's left instead of a
+    // fixed per-worker share, to avoid tail stragglers on skewed corpora.
+    pub dynamic_batch: bool,
 }
 
 impl Processor for Sais {
@@ -27,15 +99,14 @@ impl Processor for Sais {
         let total_start = Instant::now();
         let corpus_size = inputs.len();
 
-        // 1. Concatenate all inputs, track input boundaries
+        // 1. Concatenate all inputs, tracking only each input's start offset
+        // (O(num_inputs)) rather than a per-byte owner id (O(total_bytes)).
         let mut concat: Vec<u8> = Vec::new();
-        let mut input_id: Vec<usize> = Vec::new();
+        let mut offsets: Vec<usize> = Vec::with_capacity(inputs.len());
 
-        for (id, entry) in inputs.iter().enumerate() {
-            for &byte in entry {
-                concat.push(byte);
-                input_id.push(id);
-            }
+        for entry in &inputs {
+            offsets.push(concat.len());
+            concat.extend_from_slice(entry);
         }
 
         if concat.is_empty() {
@@ -43,98 +114,64 @@ impl Processor for Sais {
         }
 
         // 2. Build suffix array -> plcp -> lcp
-        let sa_result = SuffixArrayConstruction::for_text(&concat)
-            .in_owned_buffer32()
-            .single_threaded()
-            .run()
-            .ok()?;
+        let builder = SuffixArrayConstruction::for_text(&concat).in_owned_buffer32();
+        let sa_result = if self.threads > 1 {
+            builder.multi_threaded(self.threads).run().ok()?
+        } else {
+            builder.single_threaded().run().ok()?
+        };
 
+        let plcp_builder = sa_result.plcp_construction();
+        let plcp_result = if self.threads > 1 {
+            plcp_builder.multi_threaded(self.threads).run().ok()?
+        } else {
+            plcp_builder.single_threaded().run().ok()?
+        };
 
-        let plcp_result = sa_result.plcp_construction().single_threaded().run().ok()?;
-        let lcp_result = plcp_result.lcp_construction().single_threaded().run().ok()?;
+        let lcp_builder = plcp_result.lcp_construction();
+        let lcp_result = if self.threads > 1 {
+            lcp_builder.multi_threaded(self.threads).run().ok()?
+        } else {
+            lcp_builder.single_threaded().run().ok()?
+        };
         let (sa, lcp, _, _) = lcp_result.into_parts();
 
 
-        // 3. Scan LCP array using stack-based grouping
-        let mut candidates: Vec<(Vec<u8>, usize)> = Vec::new();
-        let n = sa.len();
-
-        // Stack: (lcp_level, start_pos_in_sa, input_ids)
-        let mut stack: Vec<(usize, usize, HashSet<usize>)> = Vec::new();
-
-        for i in 1..n {
-            let lcp = lcp[i] as usize;
-            let current_input = input_id[sa[i] as usize];
-            let prev_input = input_id[sa[i - 1] as usize];
-
-            // Pop and emit groups closed by this lower LCP
-            while let Some((level, _, _)) = stack.last() {
-                if lcp < *level {
-                    let (level, start, inputs) = stack.pop().unwrap();
-                    if inputs.len() >= 2 {
-                        let pos = sa[start] as usize;
-                        let len = level.min(self.max_len);
-                        if pos + len <= concat.len() {
-                            candidates.push((concat[pos..pos + len].to_vec(), inputs.len()));
-                        }
-                    }
-                } else {
-                    break;
-                }
-            }
-
-            if lcp < self.min_len {
-                continue;
-            }
-
-            // Add to existing group at same level, or push new group
-            if let Some((level, _, inputs)) = stack.last_mut() {
-                if lcp == *level {
-                    inputs.insert(current_input);
-                } else {
-                    // Rise: push new nested group
-                    let mut new_inputs = HashSet::new();
-                    new_inputs.insert(prev_input);
-                    new_inputs.insert(current_input);
-                    stack.push((lcp, i - 1, new_inputs));
-                }
-            } else {
-                // Stack empty: start new group
-                let mut new_inputs = HashSet::new();
-                new_inputs.insert(prev_input);
-                new_inputs.insert(current_input);
-                stack.push((lcp, i - 1, new_inputs));
-            }
-        }
+        // `Threshold` mode's cutoff doesn't depend on anything the scan
+        // itself produces, so it can be fused straight into `min_distinct`
+        // instead of discarding candidates after the fact. The other modes
+        // need the full candidate set to rank against, so they keep the
+        // baseline floor of 2 (a token occurring in a single input isn't a
+        // repeat).
+        let min_distinct = match &self.mode {
+            SelectionMode::Threshold(t) => (((corpus_size as f64) * t).ceil() as usize).max(1),
+            _ => 2,
+        };
 
-        // Flush remaining stack
-        while let Some((level, start, inputs)) = stack.pop() {
-            if inputs.len() >= 2 {
-                let pos = sa[start] as usize;
-                let len = level.min(self.max_len);
-                if pos + len <= concat.len() {
-                    candidates.push((concat[pos..pos + len].to_vec(), inputs.len()));
-                }
-            }
-        }
+        // 3. Scan LCP array for LCP-intervals, split across `self.threads`
+        // workers at positions where lcp[i] < min_len -- a group never
+        // straddles such a point, so each worker's range can be scanned
+        // independently and the results simply concatenated. Every interval
+        // contributes one `(token, distinct_inputs, occurrences)` candidate
+        // per length, with exact distinct-input counts from a Fenwick-tree
+        // sweep rather than a single coarse count per merged group.
+        let mut candidates = Self::parallel_lcp_scan(
+            &sa, &lcp, &offsets, &concat, self.min_len, self.max_len, min_distinct, self.maximal_only, self.threads, self.dynamic_batch,
+        );
 
         // 4. Select tokens
         let tokens: HashSet<Vec<u8>> = match &self.mode {
-            SelectionMode::Threshold(t) => {
-                let min_inputs = ((corpus_size as f64) * t).ceil() as usize;
-                let tokens = candidates.into_iter()
-                    .filter(|(_, count)| *count >= min_inputs)
-                    .map(|(token, _)| token)
-                    .collect();
-                tokens
+            SelectionMode::Threshold(_) => {
+                // Already filtered to `min_distinct` inputs by the scan above.
+                candidates.into_iter().map(|(token, _, _)| token).collect()
             }
             SelectionMode::ThresholdFn(f) => {
                 let tokens: HashSet<Vec<u8>> = candidates.into_iter()
-                    .filter(|(token, count)| {
+                    .filter(|(token, distinct, _)| {
                         let min_inputs = ((corpus_size as f64) * f.compute(token.len(), self.min_len, self.max_len)).ceil() as usize;
-                        *count >= min_inputs.max(2)  // at least 2 inputs
+                        *distinct >= min_inputs.max(2)  // at least 2 inputs
                     })
-                    .map(|(token, _)| token)
+                    .map(|(token, _, _)| token)
                     .collect();
                 self.print_threshold_curve(corpus_size, f);
                 tokens
@@ -146,15 +183,21 @@ impl Processor for Sais {
                 if candidates.is_empty() {
                     HashSet::new()
                 } else if candidates.len() <= *target {
-                    candidates.into_iter().map(|(t, _)| t).collect()
+                    candidates.into_iter().map(|(t, _, _)| t).collect()
                 } else {
                     let cutoff = candidates[target.saturating_sub(1)].1;
                     candidates.into_iter()
-                         .filter(|(_, count)| *count >= cutoff)
-                         .map(|(t, _)| t)
+                         .filter(|(_, distinct, _)| *distinct >= cutoff)
+                         .map(|(t, _, _)| t)
                          .collect()
                 }
             }
+            SelectionMode::MaxCompression { max_tokens } => {
+                Self::select_by_compression(&concat, &candidates, *max_tokens).into_iter().collect()
+            }
+            SelectionMode::WeightedRarity { max_tokens } => {
+                Self::select_by_weighted_rarity(&candidates, corpus_size, *max_tokens).into_iter().collect()
+            }
         };
 
         print_stats!(self.name(),
@@ -172,6 +215,385 @@ impl Processor for Sais {
 }
 
 impl Sais {
+    /// Bytes a dictionary index costs to reference a token, used to offset
+    /// the raw byte savings when scoring candidates for
+    /// `SelectionMode::MaxCompression`.
+    const REFERENCE_COST: f64 = 2.0;
+
+    /// Maps a concatenated-text position back to its owning input via a
+    /// binary search over sorted input start `offsets`, instead of a
+    /// per-byte owner array that costs O(total_bytes) of auxiliary memory.
+    fn input_owner(offsets: &[usize], pos: usize) -> usize {
+        offsets.partition_point(|&start| start <= pos) - 1
+    }
+
+    /// A repeat occupying SA range `[start, end)` is left-maximal iff the
+    /// byte immediately preceding each occurrence isn't the same across all
+    /// of them (an occurrence at position 0 has no preceding byte, which
+    /// trivially counts as breaking uniformity). Right-maximality is
+    /// already implied by the LCP drop that closes the group.
+    fn is_left_maximal(concat: &[u8], sa: &[u32], start: usize, end: usize) -> bool {
+        let mut preceding: Option<u8> = None;
+        for &sa_pos in &sa[start..end] {
+            let pos = sa_pos as usize;
+            if pos == 0 {
+                return true;
+            }
+            match preceding {
+                None => preceding = Some(concat[pos - 1]),
+                Some(b) if b != concat[pos - 1] => return true,
+                Some(_) => {}
+            }
+        }
+        false
+    }
+
+    /// Splits the LCP-interval scan across `threads` workers at positions
+    /// where `lcp[i] < min_len` -- safe boundaries since no interval of
+    /// interest ever straddles one -- and concatenates the per-worker
+    /// results.
+    #[allow(clippy::too_many_arguments)]
+    fn parallel_lcp_scan(
+        sa: &[u32],
+        lcp: &[i32],
+        offsets: &[usize],
+        concat: &[u8],
+        min_len: usize,
+        max_len: usize,
+        min_distinct: usize,
+        maximal_only: bool,
+        threads: usize,
+        dynamic_batch: bool,
+    ) -> Vec<(Vec<u8>, usize, usize)> {
+        let n = sa.len();
+        if n < 2 || threads <= 1 {
+            return Self::scan_lcp_range(sa, lcp, offsets, concat, min_len, max_len, min_distinct, maximal_only, 1, n);
+        }
+
+        let mut split_points: Vec<usize> = (1..n).filter(|&i| (lcp[i] as usize) < min_len).collect();
+        if split_points.last() != Some(&n) {
+            split_points.push(n);
+        }
+
+        if split_points.len() <= 1 {
+            return Self::scan_lcp_range(sa, lcp, offsets, concat, min_len, max_len, min_distinct, maximal_only, 1, n);
+        }
+
+        let chunk_target = split_points.len().div_ceil(threads).max(1);
+        let next_chunk = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            let mut handles = Vec::with_capacity(threads);
+            for _ in 0..threads {
+                let split_points = &split_points;
+                let next_chunk = &next_chunk;
+                handles.push(scope.spawn(move || {
+                    let mut local: Vec<(Vec<u8>, usize, usize)> = Vec::new();
+                    loop {
+                        // Dynamic batching: grab a variable-sized slice of the
+                        // remaining split points so no single worker is left
+                        // with a disproportionately large tail chunk.
+                        let remaining_chunks = split_points.len().saturating_sub(next_chunk.load(Ordering::Relaxed));
+                        let batch = if dynamic_batch {
+                            remaining_chunks.div_ceil(threads.max(1)).max(1)
+                        } else {
+                            chunk_target
+                        };
+
+                        let start_chunk = next_chunk.fetch_add(batch, Ordering::Relaxed);
+                        if start_chunk >= split_points.len() {
+                            break;
+                        }
+                        let end_chunk = (start_chunk + batch).min(split_points.len());
+
+                        let range_start = if start_chunk == 0 { 1 } else { split_points[start_chunk - 1] };
+                        let range_end = split_points[end_chunk - 1];
+
+                        if range_start < range_end {
+                            local.extend(Self::scan_lcp_range(
+                                sa, lcp, offsets, concat, min_len, max_len, min_distinct, maximal_only, range_start, range_end,
+                            ));
+                        }
+                    }
+                    local
+                }));
+            }
+
+            handles.into_iter().flat_map(|h| h.join().unwrap_or_default()).collect()
+        })
+    }
+
+    /// Enumerates LCP-intervals over the half-open SA index range
+    /// `[start, end)` via the classic stack algorithm and emits one
+    /// `(token, distinct_input_count, occurrence_count)` candidate per
+    /// length in each interval's range, with exact distinct-document counts
+    /// rather than one coarse count per merged group.
+    ///
+    /// For every domain index `i`, `D[i]` is the input `sa[i]` belongs to
+    /// (via `input_owner`). An LCP-interval `[lb, rb]` with interval LCP
+    /// `ell` and enclosing parent LCP `parent` contributes every length in
+    /// `max(min_len, parent + 1)..=min(ell, max_len)`, each occurring in
+    /// exactly the distinct inputs touched by `[lb, rb]`. That count is
+    /// answered offline: `prev[i]` is the nearest earlier domain index
+    /// sharing `D[i]` (or none), and the number of distinct inputs in
+    /// `[lb, rb]` equals the number of `i` in that range with `prev[i] < lb`
+    /// -- i.e. `i` is each input's first occurrence in the range. Queries
+    /// are sorted by `lb` ascending and answered with a Fenwick tree,
+    /// activating each `i` once `prev[i] < lb` becomes true for the current
+    /// query. Intervals touching fewer than `min_distinct` inputs are
+    /// dropped right here, so a tight `Threshold` cutoff never manifests as
+    /// discardable candidates later.
+    #[allow(clippy::too_many_arguments)]
+    fn scan_lcp_range(
+        sa: &[u32],
+        lcp: &[i32],
+        offsets: &[usize],
+        concat: &[u8],
+        min_len: usize,
+        max_len: usize,
+        min_distinct: usize,
+        maximal_only: bool,
+        start: usize,
+        end: usize,
+    ) -> Vec<(Vec<u8>, usize, usize)> {
+        let lo = start.saturating_sub(1);
+        if end <= lo + 1 {
+            return Vec::new();
+        }
+
+        // `prev[i]` (relative to `lo`) is the nearest earlier domain index
+        // with the same input, or `-1` if `i` is that input's first
+        // occurrence in this range.
+        let mut last_seen: HashMap<usize, i64> = HashMap::new();
+        let mut prev: Vec<i64> = vec![-1; end - lo];
+        for i in lo..end {
+            let owner = Self::input_owner(offsets, sa[i] as usize);
+            if let Some(&p) = last_seen.get(&owner) {
+                prev[i - lo] = p;
+            }
+            last_seen.insert(owner, i as i64);
+        }
+
+        // Classic LCP-interval enumeration: a stack of open intervals keyed
+        // by their LCP value, closed off whenever a smaller LCP is seen.
+        struct Frame { lcp: usize, lb: usize }
+        let mut stack = vec![Frame { lcp: 0, lb: lo }];
+        let mut intervals: Vec<(usize, usize, usize, usize)> = Vec::new(); // (lb, rb, ell, parent)
+
+        for i in (lo + 1)..end {
+            let cur = lcp[i] as usize;
+            let mut lb = i - 1;
+            while stack.last().unwrap().lcp > cur {
+                let frame = stack.pop().unwrap();
+                let rb = i - 1;
+                if rb > frame.lb {
+                    intervals.push((frame.lb, rb, frame.lcp, stack.last().unwrap().lcp));
+                }
+                lb = frame.lb;
+            }
+            if stack.last().unwrap().lcp < cur {
+                stack.push(Frame { lcp: cur, lb });
+            }
+        }
+        let rb = end - 1;
+        while stack.len() > 1 {
+            let frame = stack.pop().unwrap();
+            if rb > frame.lb {
+                intervals.push((frame.lb, rb, frame.lcp, stack.last().unwrap().lcp));
+            }
+        }
+
+        if intervals.is_empty() {
+            return Vec::new();
+        }
+
+        // Offline distinct-input-count sweep: activate domain indices in
+        // order of their `prev` value, answering each interval once every
+        // `i` with `prev[i] < lb` has been activated.
+        let mut order: Vec<usize> = (lo..end).collect();
+        order.sort_by_key(|&i| prev[i - lo]);
+
+        let mut queries: Vec<usize> = (0..intervals.len()).collect();
+        queries.sort_by_key(|&qi| intervals[qi].0);
+
+        let mut fenwick = Fenwick::new(end - lo);
+        let mut ptr = 0;
+        let mut distinct_count = vec![0usize; intervals.len()];
+        for qi in queries {
+            let (lb, rb, _, _) = intervals[qi];
+            while ptr < order.len() && prev[order[ptr] - lo] < lb as i64 {
+                fenwick.add(order[ptr] - lo, 1);
+                ptr += 1;
+            }
+            distinct_count[qi] = fenwick.range_sum(lb - lo, rb - lo);
+        }
+
+        let mut candidates: Vec<(Vec<u8>, usize, usize)> = Vec::new();
+        for (idx, &(lb, rb, ell, parent)) in intervals.iter().enumerate() {
+            let count = distinct_count[idx];
+            if count < min_distinct {
+                continue;
+            }
+            if maximal_only && !Self::is_left_maximal(concat, sa, lb, rb + 1) {
+                continue;
+            }
+
+            let pos = sa[lb] as usize;
+            let lo_len = (parent + 1).max(min_len);
+            // Inputs are concatenated with no separators, so a suffix that
+            // runs past its own input's end keeps "matching" by reading into
+            // the next input's bytes -- which isn't really an occurrence in
+            // either input. Cap the emitted length at the shortest distance
+            // to an input boundary across *every* occurrence in `[lb, rb]`,
+            // not just the representative `sa[lb]`, so `distinct_count`
+            // (computed once for the whole interval) stays exact for every
+            // length up to `hi_len`: no counted occurrence can cross a
+            // boundary at or below that length.
+            let boundary_cap = sa[lb..=rb]
+                .iter()
+                .map(|&sa_pos| {
+                    let p = sa_pos as usize;
+                    let owner = Self::input_owner(offsets, p);
+                    let doc_end = offsets.get(owner + 1).copied().unwrap_or(concat.len());
+                    doc_end - p
+                })
+                .min()
+                .unwrap_or(0);
+            let hi_len = ell.min(max_len).min(boundary_cap);
+            if lo_len > hi_len {
+                continue;
+            }
+
+            // Every suffix in `[lb, rb]` shares this prefix by construction
+            // of the interval, so the interval's width is exactly the
+            // occurrence count at any length up to `ell` -- no separate
+            // counting pass needed.
+            let occurrences = rb - lb + 1;
+            for len in lo_len..=hi_len {
+                candidates.push((concat[pos..pos + len].to_vec(), count, occurrences));
+            }
+        }
+
+        candidates
+    }
+
+    /// Counts non-overlapping occurrences of `token` in `concat` that fall
+    /// over currently-uncovered bytes, via a greedy left-to-right sweep.
+    fn uncovered_occurrences(token: &[u8], concat: &[u8], covered: &[bool]) -> usize {
+        if token.is_empty() {
+            return 0;
+        }
+        let mut count = 0;
+        let mut pos = 0;
+        while pos + token.len() <= concat.len() {
+            if &concat[pos..pos + token.len()] == token && !covered[pos..pos + token.len()].iter().any(|&c| c) {
+                count += 1;
+                pos += token.len(); // non-overlapping: skip past this match
+            } else {
+                pos += 1;
+            }
+        }
+        count
+    }
+
+    fn utility(token: &[u8], occurrences: usize) -> f64 {
+        occurrences as f64 * (token.len() as f64 - Self::REFERENCE_COST)
+    }
+
+    /// Best-first, set-cover-style token selection: repeatedly pick the
+    /// candidate with the highest `utility = non_overlapping_occurrences *
+    /// (len - reference_cost)`, mark the bytes it covers, and lazily
+    /// invalidate stale heap entries whose cached utility no longer matches
+    /// their utility over the remaining uncovered corpus before re-pushing
+    /// them.
+    fn select_by_compression(
+        concat: &[u8],
+        candidates: &[(Vec<u8>, usize, usize)],
+        max_tokens: usize,
+    ) -> Vec<Vec<u8>> {
+        let mut covered = vec![false; concat.len()];
+        let mut heap: std::collections::BinaryHeap<HeapEntry> =
+            std::collections::BinaryHeap::with_capacity(candidates.len());
+
+        for (idx, (token, _, _)) in candidates.iter().enumerate() {
+            let occurrences = Self::uncovered_occurrences(token, concat, &covered);
+            let u = Self::utility(token, occurrences);
+            if u > 0.0 {
+                heap.push(HeapEntry(u, idx));
+            }
+        }
+
+        let mut selected = Vec::new();
+        while selected.len() < max_tokens {
+            let Some(HeapEntry(stale_utility, idx)) = heap.pop() else { break };
+            let token = &candidates[idx].0;
+
+            // Re-derive utility against the bytes still uncovered; if the
+            // stored value is stale (an earlier pick covered some of this
+            // token's occurrences), push the refreshed entry back instead of
+            // accepting it.
+            let occurrences = Self::uncovered_occurrences(token, concat, &covered);
+            let current_utility = Self::utility(token, occurrences);
+            if (current_utility - stale_utility).abs() > f64::EPSILON && current_utility > 0.0 {
+                heap.push(HeapEntry(current_utility, idx));
+                continue;
+            }
+            if current_utility <= 0.0 {
+                continue;
+            }
+
+            // Mark the bytes this token covers (same greedy sweep as above).
+            let mut pos = 0;
+            while pos + token.len() <= concat.len() {
+                if &concat[pos..pos + token.len()] == token.as_slice()
+                    && !covered[pos..pos + token.len()].iter().any(|&c| c)
+                {
+                    for c in &mut covered[pos..pos + token.len()] { *c = true; }
+                    pos += token.len();
+                } else {
+                    pos += 1;
+                }
+            }
+
+            selected.push(token.clone());
+        }
+
+        selected
+    }
+
+    /// Term-frequency times inverse-document-frequency over the candidate's
+    /// own occurrence/distinct-document counts: high when a token recurs
+    /// several times within the same handful of inputs, low when it's either
+    /// a one-off or spread evenly across (almost) the whole corpus.
+    fn rarity_weight(distinct: usize, occurrences: usize, total_docs: usize) -> f64 {
+        let tf = occurrences as f64 / distinct as f64;
+        let idf = (total_docs as f64 / distinct as f64).ln();
+        tf * idf
+    }
+
+    /// Selects the top `max_tokens` candidates by [`Self::rarity_weight`].
+    fn select_by_weighted_rarity(
+        candidates: &[(Vec<u8>, usize, usize)],
+        total_docs: usize,
+        max_tokens: usize,
+    ) -> Vec<Vec<u8>> {
+        let mut scored: Vec<(&Vec<u8>, f64)> = candidates
+            .iter()
+            .map(|(token, distinct, occurrences)| {
+                (token, Self::rarity_weight(*distinct, *occurrences, total_docs))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.dedup_by(|a, b| a.0 == b.0);
+        scored
+            .into_iter()
+            .take(max_tokens)
+            .filter(|(_, score)| *score > 0.0)
+            .map(|(token, _)| token.clone())
+            .collect()
+    }
+
     fn print_threshold_curve(&self, corpus_size: usize, f: &ThresholdFunction) {
         let points = [0.0, 0.25, 0.5, 0.75, 1.0];
         let values: Vec<String> = points.iter().map(|&p| {
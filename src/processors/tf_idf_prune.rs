@@ -0,0 +1,86 @@
+use crate::config::config;
+use crate::print_stats;
+use super::Processor;
+
+/// Ranks candidate tokens by information content instead of raw frequency
+/// and keeps only the most informative ones. Unlike `RemoveRepetitive`/
+/// `FilterNullBytes`, which judge a token in isolation, this looks at how
+/// the token relates to the rest of the candidate set: a TF-IDF-like score
+/// pushes down fragments that show up in nearly every input (too common to
+/// be interesting) as well as long random-looking blobs (too noisy to be a
+/// reusable token), while keeping moderately common, structured delimiters.
+pub struct TfIdfPrune {
+    // Keep only the `top_k` highest-scoring tokens, if set.
+    pub top_k: Option<usize>,
+    // Keep only tokens scoring at or above this threshold, if set. When both
+    // `top_k` and `min_score` are set, the threshold is applied first and
+    // `top_k` caps the survivors.
+    pub min_score: Option<f64>,
+}
+
+impl Processor for TfIdfPrune {
+    fn process(&self, inputs: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+        let original_count = inputs.len();
+        let corpus_len = inputs.len();
+
+        let mut scored: Vec<(Vec<u8>, f64)> = inputs.iter()
+            .map(|token| {
+                let df = inputs.iter().filter(|other| Self::contains(other, token)).count();
+                let idf = (corpus_len as f64 / (1.0 + df as f64)).ln();
+                let structure = 1.0 - Self::normalized_entropy(token);
+                (token.clone(), df as f64 * idf * structure)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        if let Some(min_score) = self.min_score {
+            scored.retain(|(_, score)| *score >= min_score);
+        }
+        if let Some(top_k) = self.top_k {
+            scored.truncate(top_k);
+        }
+
+        if !config().silent_run {
+            print_stats!(self.name(), "Kept {}/{} tokens by information content.",
+                scored.len(), original_count);
+        }
+
+        let result: Vec<Vec<u8>> = scored.into_iter().map(|(token, _)| token).collect();
+        if result.is_empty() { None } else { Some(result) }
+    }
+
+    fn name(&self) -> &'static str { "tf_idf_prune" }
+}
+
+impl TfIdfPrune {
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        if needle.is_empty() {
+            return true;
+        }
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    /// Shannon entropy of `token`'s byte distribution, normalized to 0..1 by
+    /// dividing by the 8 bits/byte maximum. `1 - this` is used as a
+    /// structure factor in `process`, so near-random blobs (entropy close to
+    /// 1) are penalized while repetitive/structured tokens pass through.
+    fn normalized_entropy(token: &[u8]) -> f64 {
+        if token.is_empty() {
+            return 0.0;
+        }
+        let mut counts = [0usize; 256];
+        for &b in token {
+            counts[b as usize] += 1;
+        }
+        let len = token.len() as f64;
+        let entropy: f64 = counts.iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| {
+                let p = c as f64 / len;
+                -p * p.log2()
+            })
+            .sum();
+        entropy / 8.0
+    }
+}
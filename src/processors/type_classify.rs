@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::config::config;
+use crate::print_stats;
+use super::Processor;
+
+/// Integer width a classified token may be re-rendered as, in both
+/// endiannesses.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum IntWidth {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntWidth {
+    fn min(self) -> i128 {
+        match self {
+            Self::I8 => i8::MIN as i128,
+            Self::I16 => i16::MIN as i128,
+            Self::I32 => i32::MIN as i128,
+            Self::I64 => i64::MIN as i128,
+            Self::U8 | Self::U16 | Self::U32 | Self::U64 => 0,
+        }
+    }
+
+    fn max(self) -> i128 {
+        match self {
+            Self::I8 => i8::MAX as i128,
+            Self::I16 => i16::MAX as i128,
+            Self::I32 => i32::MAX as i128,
+            Self::I64 => i64::MAX as i128,
+            Self::U8 => u8::MAX as i128,
+            Self::U16 => u16::MAX as i128,
+            Self::U32 => u32::MAX as i128,
+            Self::U64 => u64::MAX as i128,
+        }
+    }
+
+    fn encode(self, value: i128, little_endian: bool) -> Option<Vec<u8>> {
+        if value < self.min() || value > self.max() {
+            return None;
+        }
+        Some(match (self, little_endian) {
+            (Self::I8, true) => (value as i8).to_le_bytes().to_vec(),
+            (Self::I8, false) => (value as i8).to_be_bytes().to_vec(),
+            (Self::I16, true) => (value as i16).to_le_bytes().to_vec(),
+            (Self::I16, false) => (value as i16).to_be_bytes().to_vec(),
+            (Self::I32, true) => (value as i32).to_le_bytes().to_vec(),
+            (Self::I32, false) => (value as i32).to_be_bytes().to_vec(),
+            (Self::I64, true) => (value as i64).to_le_bytes().to_vec(),
+            (Self::I64, false) => (value as i64).to_be_bytes().to_vec(),
+            (Self::U8, true) => (value as u8).to_le_bytes().to_vec(),
+            (Self::U8, false) => (value as u8).to_be_bytes().to_vec(),
+            (Self::U16, true) => (value as u16).to_le_bytes().to_vec(),
+            (Self::U16, false) => (value as u16).to_be_bytes().to_vec(),
+            (Self::U32, true) => (value as u32).to_le_bytes().to_vec(),
+            (Self::U32, false) => (value as u32).to_be_bytes().to_vec(),
+            (Self::U64, true) => (value as u64).to_le_bytes().to_vec(),
+            (Self::U64, false) => (value as u64).to_be_bytes().to_vec(),
+        })
+    }
+}
+
+/// Classifies each token as an `Integer`, `Float`, `Timestamp`, or raw
+/// `Bytes` token, and expands recognized integers/timestamps into
+/// high-value boundary variants.
+pub struct TypeClassify {
+    pub widths: Vec<IntWidth>,
+    pub timestamp_formats: Vec<String>,
+}
+
+impl TypeClassify {
+    /// Canonical integer neighbors (`0`, `1`, `value ± 1`, and each
+    /// configured width's min/max), rendered in both endiannesses for every
+    /// configured width.
+    fn integer_variants(&self, value: i128) -> Vec<Vec<u8>> {
+        let mut candidates = vec![0i128, 1, value, value.saturating_sub(1), value.saturating_add(1)];
+        for width in &self.widths {
+            candidates.push(width.min());
+            candidates.push(width.max());
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let mut out = Vec::new();
+        for candidate in candidates {
+            for width in &self.widths {
+                out.extend(width.encode(candidate, true));
+                out.extend(width.encode(candidate, false));
+            }
+        }
+        out
+    }
+
+    fn timestamp_variants(&self, text: &str) -> Option<Vec<Vec<u8>>> {
+        for fmt in &self.timestamp_formats {
+            if let Ok(dt) = NaiveDateTime::parse_from_str(text, fmt) {
+                return Some(
+                    self.timestamp_formats
+                        .iter()
+                        .map(|out_fmt| dt.format(out_fmt).to_string().into_bytes())
+                        .collect(),
+                );
+            }
+        }
+        None
+    }
+}
+
+impl Processor for TypeClassify {
+    fn process(&self, inputs: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+        let original_count = inputs.len();
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        let mut expanded = 0usize;
+
+        for token in inputs {
+            if seen.insert(token.clone()) {
+                result.push(token.clone());
+            }
+
+            let Ok(text) = std::str::from_utf8(&token) else {
+                continue;
+            };
+            let text = text.trim();
+
+            let variants = if let Ok(n) = text.parse::<i128>() {
+                self.integer_variants(n)
+            } else if text.parse::<f64>().is_ok() {
+                // Recognized as a float; no boundary expansion is defined for
+                // floats, keep the original token as-is.
+                Vec::new()
+            } else {
+                self.timestamp_variants(text).unwrap_or_default()
+            };
+
+            for variant in variants {
+                if seen.insert(variant.clone()) {
+                    expanded += 1;
+                    result.push(variant);
+                }
+            }
+        }
+
+        if !config().silent_run {
+            print_stats!(
+                self.name(),
+                "Classified {} tokens, added {} boundary variants.",
+                original_count,
+                expanded
+            );
+        }
+
+        if result.is_empty() { None } else { Some(result) }
+    }
+
+    fn name(&self) -> &'static str { "type_classify" }
+}
@@ -2,11 +2,29 @@ use libafl::{mutators::{Mutator, MutationResult}, state::{HasRand, HasMaxSize},
 use libafl_bolts::{Named};
 use serde::{Serialize, Deserialize};
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::num::NonZero;
 use std::slice::Iter;
 use libafl_bolts::rands::Rand;
 
+/// Wraps an `f64` so it can be used as a `BinaryHeap`/`Ord` key -- used for
+/// both success rates and UCB1 scores, neither of which are ever `NaN` in
+/// practice here.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct OrderedF64(f64);
+impl Eq for OrderedF64 {}
+impl PartialOrd for OrderedF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for OrderedF64 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
 #[expect(clippy::unsafe_derive_deserialize)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SmartTokens {
@@ -15,6 +33,32 @@ pub struct SmartTokens {
     stats: Vec<TokenStat>,
     max_tokens: usize,
     protected_idx: Option<usize>,  // index currently in use
+    // Removable priority queue over success rate, used by
+    // `find_eviction_index` to find the worst token in amortized O(log n):
+    // a lazily-deleted min-heap keyed on `(rate, generation, idx)`, where a
+    // popped entry is a stale tombstone iff its generation doesn't match
+    // `generation[idx]` anymore.
+    eviction_heap: BinaryHeap<Reverse<(OrderedF64, u64, usize)>>,
+    generation: Vec<u64>,
+    // UCB1-style selection: `score_heap` is a max-heap over
+    // `(score, generation, idx)`, lazily invalidated the same way as
+    // `eviction_heap` (sharing the same `generation` counter), used by
+    // `select_token` to bias sampling toward high-reward tokens.
+    score_heap: BinaryHeap<(OrderedF64, u64, usize)>,
+    total_uses: u64,
+    exploration_c: f64,
+    // Co-occurrence graph: how often two token indices were both inserted
+    // into an input that went on to hit new coverage, keyed by `(min, max)`
+    // index pair. Feeds `to_dot`.
+    co_occurrence: HashMap<(usize, usize), u64>,
+    // Token indices inserted since the last evaluation, not yet committed
+    // into `co_occurrence`.
+    pending_uses: Vec<usize>,
+    // Liveness score driving `decay_and_prune`: bumped on every use that
+    // goes on to hit new coverage, decayed by a configurable factor once per
+    // discovery cycle, and evicted once it reaches zero (outside the
+    // probation window). Parallel to `tokens_vec`/`stats`/`generation`.
+    liveness: Vec<f64>,
 }
 
 libafl_bolts::impl_serdeany!(SmartTokens);
@@ -30,6 +74,23 @@ impl SmartTokens {
     /// limit how many tokens we can have
     const DEFAULT_MAX_TOKENS: usize = 100;
 
+    /// Default UCB1 exploration constant (the classic `sqrt(2)`), overridable
+    /// via `set_exploration_constant`.
+    const DEFAULT_EXPLORATION_C: f64 = std::f64::consts::SQRT_2;
+
+    /// Starting liveness score for a freshly (re)inserted token.
+    const DEFAULT_LIVENESS: f64 = 1.0;
+
+    /// `eviction_heap`/`score_heap` get a fresh entry pushed on every stat
+    /// change, so a long campaign re-pushing the same few live tokens over
+    /// and over would otherwise grow the heaps without bound even though
+    /// `max_tokens` caps the live set -- stale entries are only reclaimed
+    /// lazily, on pop, by `find_eviction_index`/`select_token`. Once a heap
+    /// holds more than this many entries per live token (and more than
+    /// `DEFAULT_MAX_TOKENS` entries in all), `maybe_compact_heaps` rebuilds
+    /// it from scratch with exactly one entry per live index.
+    const COMPACTION_RATIO: usize = 8;
+
     /// Creates a new SmartTokens metadata with default capacity
     #[must_use]
     pub fn new() -> Self {
@@ -44,8 +105,87 @@ impl SmartTokens {
             tokens_set: HashSet::with_capacity(max_tokens),
             stats: Vec::with_capacity(max_tokens),
             max_tokens,
-            protected_idx: None
+            protected_idx: None,
+            eviction_heap: BinaryHeap::with_capacity(max_tokens),
+            generation: Vec::with_capacity(max_tokens),
+            score_heap: BinaryHeap::with_capacity(max_tokens),
+            total_uses: 0,
+            exploration_c: Self::DEFAULT_EXPLORATION_C,
+            co_occurrence: HashMap::new(),
+            pending_uses: Vec::new(),
+            liveness: Vec::with_capacity(max_tokens),
+        }
+    }
+
+    /// Sets the UCB1 exploration constant `c` used by `select_token`; higher
+    /// values favor trying under-sampled tokens over exploiting the current
+    /// best one.
+    pub fn set_exploration_constant(&mut self, c: f64) {
+        self.exploration_c = c;
+    }
+
+    /// Marks `idx` as used by a token mutation this round, pending
+    /// commitment into the co-occurrence graph once we know whether the
+    /// resulting input hit new coverage.
+    pub fn record_pending_use(&mut self, idx: usize) {
+        self.pending_uses.push(idx);
+    }
+
+    /// Commits this round's pending token uses into the co-occurrence graph
+    /// (every pair gets an edge) when `success` is true, then clears them
+    /// either way.
+    pub fn commit_pending_uses(&mut self, success: bool) {
+        if success {
+            for i in 0..self.pending_uses.len() {
+                for j in (i + 1)..self.pending_uses.len() {
+                    let (a, b) = (self.pending_uses[i], self.pending_uses[j]);
+                    let key = if a <= b { (a, b) } else { (b, a) };
+                    *self.co_occurrence.entry(key).or_insert(0) += 1;
+                }
+            }
+        }
+        self.pending_uses.clear();
+    }
+
+    /// Serializes the co-occurrence graph as Graphviz `digraph` source,
+    /// limited to the `max_nodes` most-used tokens so the graph stays
+    /// readable. Nodes are escaped token previews; edges are labeled with
+    /// their co-occurrence count.
+    #[must_use]
+    pub fn to_dot(&self, max_nodes: usize) -> String {
+        let mut top: Vec<usize> = (0..self.tokens_vec.len()).collect();
+        top.sort_by_key(|&i| std::cmp::Reverse(self.stats[i].uses));
+        top.truncate(max_nodes);
+        let kept: HashSet<usize> = top.iter().copied().collect();
+
+        let mut out = String::from("digraph tokens {\n");
+        for &idx in &top {
+            out.push_str(&format!(
+                "    n{idx} [label=\"{}\"];\n",
+                Self::escape_preview(&self.tokens_vec[idx])
+            ));
+        }
+        for (&(a, b), &count) in &self.co_occurrence {
+            if kept.contains(&a) && kept.contains(&b) {
+                out.push_str(&format!("    n{a} -> n{b} [label=\"{count}\"];\n"));
+            }
         }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Short, Graphviz-escaped preview of a token for use as a node label.
+    fn escape_preview(token: &[u8]) -> String {
+        const PREVIEW_LEN: usize = 24;
+        token
+            .iter()
+            .take(PREVIEW_LEN)
+            .map(|&b| match b {
+                b'"' | b'\\' => format!("\\{}", b as char),
+                0x20..=0x7e => (b as char).to_string(),
+                _ => format!("\\\\x{b:02x}"),
+            })
+            .collect()
     }
 
     /// protect the token currently in use from being replaced
@@ -65,56 +205,182 @@ impl SmartTokens {
         if self.tokens_set.contains(token) {
             return None;
         }
-        if self.tokens_vec.len() < self.max_tokens {
+        let result = if self.tokens_vec.len() < self.max_tokens {
             self.tokens_vec.push(token.clone());
             self.tokens_set.insert(token.clone());
             self.stats.push(TokenStat::default());
-            Some(self.tokens_vec.len() - 1)
+            self.liveness.push(Self::DEFAULT_LIVENESS);
+            let idx = self.tokens_vec.len() - 1;
+            self.generation.push(0);
+            self.total_uses += 1;
+            self.eviction_heap.push(Reverse((OrderedF64(Self::rate(&self.stats[idx])), 0, idx)));
+            self.score_heap.push((
+                OrderedF64(Self::score(&self.stats[idx], self.total_uses, self.exploration_c)), 0, idx,
+            ));
+            Some(idx)
         } else {
             match self.find_eviction_index() {
                 Some(idx) => {
                     self.tokens_set.remove(&self.tokens_vec[idx]);
                     self.tokens_set.insert(token.clone());
                     self.tokens_vec[idx] = token.clone(); // replace old token
+                    self.total_uses = self.total_uses - self.stats[idx].uses + 1;
                     self.stats[idx] = TokenStat::default();
+                    self.liveness[idx] = Self::DEFAULT_LIVENESS;
+
+                    self.generation[idx] += 1;
+                    self.eviction_heap.push(Reverse((
+                        OrderedF64(Self::rate(&self.stats[idx])), self.generation[idx], idx,
+                    )));
+                    self.score_heap.push((
+                        OrderedF64(Self::score(&self.stats[idx], self.total_uses, self.exploration_c)),
+                        self.generation[idx], idx,
+                    ));
 
                     Some(idx)
                 },
                 None => None // reject new token
             }
+        };
+
+        self.maybe_compact_heaps();
+        result
+    }
+
+    /// Adds multiple tokens at once, skipping duplicates exactly like
+    /// `add_token`.
+    pub fn add_tokens(&mut self, tokens: &[Vec<u8>]) {
+        for token in tokens {
+            self.add_token(token);
         }
     }
 
-    /// Determine which tokens to drop whenever the limit is reached.
-    fn find_eviction_index(&self) -> Option<usize> {
-        // first try to sort out unuseful ones
-        let mut worst_idx: usize = 0;
-        let mut worst_rate = f64::MAX;
+    /// Reads an AFL/libafl `-x` dictionary file and merges its entries into
+    /// this set through the usual `add_token` dedup/eviction path. Returns
+    /// the number of entries parsed out of the file (not the number actually
+    /// added, since duplicates are silently skipped).
+    pub fn merge_dict_file(&mut self, path: &str) -> Result<usize, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::os_error(e, format!("failed to read dictionary file {path}")))?;
+        let tokens = crate::dict_format::parse_dict(&contents);
+        let count = tokens.len();
+        self.add_tokens(&tokens);
+        Ok(count)
+    }
 
-        for (i, stat) in self.stats.iter().enumerate() {
-            if Some(i) == self.protected_idx {
-                continue // don't remove the index we just used.
-            }
+    /// Creates a new `SmartTokens` with default capacity, seeded from an
+    /// AFL/libafl `-x` dictionary file. Lets hand-written protocol
+    /// dictionaries give `SmartTokenInsert`/`SmartTokenReplace` a warm start
+    /// before suffix-array discovery produces anything of its own.
+    pub fn from_dict_file(path: &str) -> Result<Self, Error> {
+        let mut tokens = Self::new();
+        tokens.merge_dict_file(path)?;
+        Ok(tokens)
+    }
 
-            if stat.uses > 0 {
-                let rate = stat.successes as f64 / stat.uses as f64;
-                if rate < worst_rate {
-                    worst_rate = rate;
-                    worst_idx = i;
-                }
+    /// The success rate a `find_eviction_index` tie-break / heap key is
+    /// derived from.
+    #[inline]
+    fn rate(stat: &TokenStat) -> f64 {
+        stat.successes as f64 / stat.uses as f64
+    }
+
+    /// Determine which tokens to drop whenever the limit is reached, by
+    /// popping the worst (lowest success-rate) live entry off `eviction_heap`.
+    /// Entries whose generation no longer matches `generation[idx]` are stale
+    /// tombstones and are discarded; the protected index is never returned,
+    /// but its entry is re-pushed so it can still be evicted later.
+    fn find_eviction_index(&mut self) -> Option<usize> {
+        let mut held = Vec::new();
+
+        let result = loop {
+            let Some(entry) = self.eviction_heap.pop() else {
+                break None;
+            };
+            let Reverse((_, generation, idx)) = entry;
+
+            if self.generation.get(idx) != Some(&generation) {
+                continue; // stale tombstone, a fresher entry for `idx` exists (or `idx` was pruned)
             }
+            if Some(idx) == self.protected_idx {
+                held.push(entry);
+                continue;
+            }
+
+            break Some(idx);
+        };
+
+        for entry in held {
+            self.eviction_heap.push(entry);
         }
 
-        // Don't return protected index
-        if Some(worst_idx) == self.protected_idx {
-            return None;
+        result
+    }
+
+    /// Decays every token's liveness score by `decay_factor`, then prunes
+    /// any non-protected, out-of-probation token whose score has reached
+    /// zero. Meant to be called once per `TokenDiscoveryStage` cycle so the
+    /// dictionary self-prunes instead of growing monotonically.
+    ///
+    /// Dead indices are removed via `swap_remove`, highest index first, so
+    /// the moved-in replacement at each removed slot is always a surviving
+    /// token, never one still waiting to be removed; its generation is
+    /// bumped. Because pruning reshuffles indices, and a later `add_token`
+    /// can reuse a freed index starting again at generation 0, both heaps
+    /// are rebuilt from the surviving tokens whenever anything was pruned,
+    /// so no entry left over from a removed token can look current.
+    pub fn decay_and_prune(&mut self, decay_factor: f64, probation_uses: u64) {
+        for score in &mut self.liveness {
+            *score *= decay_factor;
         }
 
-        if worst_rate > 1.0 {
-            return None  // No token has been used yet
+        // `uses` counts from the `TokenStat::default` sentinel of 1.
+        let mut dead: Vec<usize> = (0..self.tokens_vec.len())
+            .filter(|&idx| {
+                Some(idx) != self.protected_idx
+                    && self.stats[idx].uses.saturating_sub(1) >= probation_uses
+                    && self.liveness[idx] <= 0.0
+            })
+            .collect();
+        dead.sort_unstable_by(|a, b| b.cmp(a));
+        let pruned = !dead.is_empty();
+
+        for idx in dead {
+            let last = self.tokens_vec.len() - 1;
+
+            self.tokens_set.remove(&self.tokens_vec[idx]);
+            self.tokens_vec.swap_remove(idx);
+            self.stats.swap_remove(idx);
+            self.liveness.swap_remove(idx);
+            self.generation.swap_remove(idx);
+
+            if idx != last {
+                if self.protected_idx == Some(last) {
+                    self.protected_idx = Some(idx);
+                }
+                self.generation[idx] += 1;
+                self.eviction_heap.push(Reverse((
+                    OrderedF64(Self::rate(&self.stats[idx])), self.generation[idx], idx,
+                )));
+                self.score_heap.push((
+                    OrderedF64(Self::score(&self.stats[idx], self.total_uses, self.exploration_c)),
+                    self.generation[idx], idx,
+                ));
+            }
         }
 
-        Some(worst_idx)
+        if pruned {
+            self.eviction_heap = self.stats.iter().zip(&self.generation).enumerate()
+                .map(|(idx, (stat, &generation))| Reverse((OrderedF64(Self::rate(stat)), generation, idx)))
+                .collect();
+            self.score_heap = self.stats.iter().zip(&self.generation).enumerate()
+                .map(|(idx, (stat, &generation))| {
+                    (OrderedF64(Self::score(stat, self.total_uses, self.exploration_c)), generation, idx)
+                })
+                .collect();
+        } else {
+            self.maybe_compact_heaps();
+        }
     }
 
     /// record the use of a token
@@ -124,8 +390,99 @@ impl SmartTokens {
             stat.uses += 1;
             if success {
                 stat.successes += 1;
+                self.liveness[idx] += 1.0;
+            }
+            self.total_uses += 1;
+
+            self.generation[idx] += 1;
+            self.eviction_heap.push(Reverse((OrderedF64(Self::rate(stat)), self.generation[idx], idx)));
+            self.score_heap.push((
+                OrderedF64(Self::score(stat, self.total_uses, self.exploration_c)), self.generation[idx], idx,
+            ));
+        }
+
+        self.maybe_compact_heaps();
+    }
+
+    /// Rebuilds `eviction_heap`/`score_heap` from scratch, keeping exactly
+    /// one (current-generation) entry per live token, once either heap holds
+    /// more than `COMPACTION_RATIO` entries per live token (and more than
+    /// `DEFAULT_MAX_TOKENS` entries in all). Lazy deletion alone reclaims tombstones only as they happen to
+    /// be popped, which a token that stays live and well-scoring (so it's
+    /// never the eviction/selection winner) would never trigger -- this
+    /// keeps both heaps bounded to O(live tokens) regardless of campaign
+    /// length.
+    fn maybe_compact_heaps(&mut self) {
+        let live = self.tokens_vec.len();
+        let bound = live.saturating_mul(Self::COMPACTION_RATIO).max(Self::DEFAULT_MAX_TOKENS);
+        if self.eviction_heap.len() <= bound && self.score_heap.len() <= bound {
+            return;
+        }
+
+        self.eviction_heap = self.stats.iter().zip(&self.generation).enumerate()
+            .map(|(idx, (stat, &generation))| Reverse((OrderedF64(Self::rate(stat)), generation, idx)))
+            .collect();
+        self.score_heap = self.stats.iter().zip(&self.generation).enumerate()
+            .map(|(idx, (stat, &generation))| {
+                (OrderedF64(Self::score(stat, self.total_uses, self.exploration_c)), generation, idx)
+            })
+            .collect();
+    }
+
+    /// UCB1-style score `successes/uses + c * sqrt(ln(total_uses) / uses)`,
+    /// used by `select_token` to bias sampling toward high-reward tokens. A
+    /// token that hasn't been used since it was (re)inserted -- `uses <= 1`,
+    /// the `TokenStat::default` sentinel -- scores infinite, so it is always
+    /// tried before falling back to the bandit score.
+    #[inline]
+    fn score(stat: &TokenStat, total_uses: u64, c: f64) -> f64 {
+        if stat.uses <= 1 {
+            return f64::INFINITY;
+        }
+        let uses = stat.uses as f64;
+        stat.successes as f64 / uses + c * ((total_uses as f64).ln() / uses).sqrt()
+    }
+
+    /// Selects a token index biased toward high reward, by popping the best
+    /// live entries off `score_heap` (mirroring `find_eviction_index`'s
+    /// generation-based lazy-deletion, sharing the same `generation`
+    /// counter). A token's own entry is only refreshed when its own stats
+    /// change; it is not retroactively recomputed as `total_uses` grows from
+    /// other tokens' uses, trading exactness for amortized O(log n)
+    /// selection. Ties -- most commonly several never-used tokens at once --
+    /// are broken using `rand_seed`.
+    pub fn select_token(&mut self, rand_seed: u64) -> Option<usize> {
+        if self.tokens_vec.is_empty() {
+            return None;
+        }
+
+        let mut tied: Vec<(u64, usize)> = Vec::new();
+        let mut best_score = f64::NEG_INFINITY;
+
+        while let Some((OrderedF64(score), generation, idx)) = self.score_heap.pop() {
+            if self.generation.get(idx) != Some(&generation) {
+                continue; // stale tombstone, a fresher entry for `idx` exists (or `idx` was pruned)
+            }
+
+            if tied.is_empty() || score == best_score {
+                best_score = score;
+                tied.push((generation, idx));
+            } else {
+                self.score_heap.push((OrderedF64(score), generation, idx));
+                break;
             }
         }
+
+        if tied.is_empty() {
+            return None;
+        }
+
+        let chosen = (rand_seed as usize) % tied.len();
+        for &(generation, idx) in &tied {
+            self.score_heap.push((OrderedF64(best_score), generation, idx));
+        }
+
+        Some(tied[chosen].1)
     }
 
     /// Gets the tokens stored in this db
@@ -154,6 +511,7 @@ impl SmartToken {
         // Protect this token from eviction during execution
         if let Some(smart_tokens) = state.metadata_map_mut().get_mut::<SmartTokens>() {
             smart_tokens.protect_index(idx);
+            smart_tokens.record_pending_use(idx);
         }
         self.last_token_idx = Some(idx);
         Ok(())
@@ -169,6 +527,7 @@ impl SmartToken {
             // Unprotect and record use
             smart_tokens.unprotect();
             smart_tokens.update_stats(idx, corpus_id.is_some());
+            smart_tokens.commit_pending_uses(corpus_id.is_some());
 
             self.last_token_idx = None;
         }
@@ -191,18 +550,20 @@ where
 {
     fn mutate(&mut self, state: &mut S, input: &mut I) -> Result<MutationResult, Error> {
         let max_size = state.max_size();
-        let tokens_len = {
-            let Some(meta) = state.metadata_map().get::<SmartTokens>() else {
+
+        // Pull a random seed first: `select_token` needs a mutable borrow of
+        // the `SmartTokens` metadata, which would otherwise conflict with
+        // `state.rand_mut()`.
+        let rand_seed = state.rand_mut().next();
+        let token_idx = {
+            let Some(meta) = state.metadata_map_mut().get_mut::<SmartTokens>() else {
                 return Ok(MutationResult::Skipped);
             };
-            if let Some(tokens_len) = NonZero::new(meta.tokens().len()) {
-                tokens_len
-            } else {
+            let Some(token_idx) = meta.select_token(rand_seed) else {
                 return Ok(MutationResult::Skipped);
-            }
+            };
+            token_idx
         };
-
-        let token_idx = state.rand_mut().below(tokens_len);
         let size = input.mutator_bytes().len();
 
         // # Safety
@@ -275,17 +636,19 @@ where
             return Ok(MutationResult::Skipped);
         };
 
-        let tokens_len = {
-            let Some(meta) = state.metadata_map().get::<SmartTokens>() else {
+        // Pull a random seed first: `select_token` needs a mutable borrow of
+        // the `SmartTokens` metadata, which would otherwise conflict with
+        // `state.rand_mut()`.
+        let rand_seed = state.rand_mut().next();
+        let token_idx = {
+            let Some(meta) = state.metadata_map_mut().get_mut::<SmartTokens>() else {
                 return Ok(MutationResult::Skipped);
             };
-            if let Some(tokens_len) = NonZero::new(meta.tokens().len()) {
-                tokens_len
-            } else {
+            let Some(token_idx) = meta.select_token(rand_seed) else {
                 return Ok(MutationResult::Skipped);
-            }
+            };
+            token_idx
         };
-        let token_idx = state.rand_mut().below(tokens_len);
 
         let meta = state.metadata_map().get::<SmartTokens>().unwrap();
         let token = &meta.tokens()[token_idx];
@@ -345,6 +708,17 @@ pub struct DiscoveredTokens {
 }
 libafl_bolts::impl_serdeany!(DiscoveredTokens);
 
+impl DiscoveredTokens {
+    /// Reads an AFL/libafl `-x` dictionary file into a `DiscoveredTokens`,
+    /// for seeding this exchange struct with a hand-written dictionary
+    /// instead of (or alongside) suffix-array discovery output.
+    pub fn from_dict_file(path: &str) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| Error::os_error(e, format!("failed to read dictionary file {path}")))?;
+        Ok(Self { tokens: crate::dict_format::parse_dict(&contents) })
+    }
+}
+
 
 
 // ------------- Utilities copied from libafl mutations.rs (private) ------------- //
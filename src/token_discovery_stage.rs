@@ -1,9 +1,10 @@
 use std::{borrow::Cow, marker::PhantomData};
 use libafl::{
     corpus::{Corpus, HasCurrentCorpusId},
-    events::EventFirer,
+    events::{Event, EventFirer},
     executors::{Executor, HasObservers},
     inputs::HasTargetBytes,
+    monitors::{AggregatorOps, UserStats, UserStatsValue},
     observers::MapObserver,
     stages::{mutational::MutatedTransform, Restartable, RetryCountRestartHelper, Stage},
     state::{HasCorpus, HasCurrentTestcase, HasRand, MaybeHasClientPerfMonitor},
@@ -11,12 +12,14 @@ use libafl::{
     HasMetadata,
     HasNamedMetadata
 };
-use libafl_bolts::{tuples::{Handled, MatchNameRef}, Named};
+use libafl_bolts::{tuples::{Handle, Handled, MatchNameRef}, Named};
 
+use crate::cmp_observer::{search_tokens_from_cmp, CmpMap, CmpMapObserver};
 use crate::config::config;
 use crate::extractors::Extractor;
 use crate::processors::Processor;
 use crate::smart_token_mutations::SmartTokens;
+use crate::tokens::network_token_sync::TokenSyncClient;
 
 pub const STAGE_NAME: &str = "TokenDiscoveryStage";
 
@@ -26,6 +29,15 @@ pub struct TokenDiscoveryStage<E, EM, I, S, Z, C, O> {
     extractor: Extractor<C>,
     processors: Vec<Box<dyn Processor>>,
     stage_calls: u32,
+    // Cross-host token pooling (chunk1-1); `None` when `token_sync` isn't
+    // configured, which keeps single-host campaigns exactly as before.
+    token_sync: Option<TokenSyncClient>,
+    sync_calls: u32,
+    dot_calls: u32,
+    // Input-to-state token discovery (chunk3-1): handle of the `CmpMapObserver`
+    // sharing the executor's observer tuple, read every call alongside the
+    // coverage-diff-based extractor above.
+    cmp_handle: Handle<CmpMapObserver>,
     phantom: PhantomData<(E, EM, I, S, Z, O)>,
 }
 
@@ -76,31 +88,153 @@ where
             None => return Ok(()),
         };
 
-        // 2. Run through pipeline
+        // 2. Run through pipeline, firing a UserStats event per processor so
+        // the pipeline's per-stage attrition is visible through the normal
+        // LibAFL UI/logging path instead of stdout (invisible to monitors
+        // and lost in restartable multi-core runs).
         for proc in &self.processors {
+            let before = data.len();
             data = match proc.process(data) {
                 Some(d) => d,
                 None => return Ok(()),
             };
+            let _ = manager.fire(
+                state,
+                Event::UpdateUserStats {
+                    name: Cow::Owned(format!("{}_kept", proc.name())),
+                    value: UserStats::new(UserStatsValue::Ratio(data.len() as u64, before.max(1) as u64), AggregatorOps::Avg),
+                    phantom: PhantomData,
+                },
+            );
         }
 
+        let _ = manager.fire(
+            state,
+            Event::UpdateUserStats {
+                name: Cow::Borrowed("token_discovery_tokens_found"),
+                value: UserStats::new(UserStatsValue::Number(data.len() as u64), AggregatorOps::Avg),
+                phantom: PhantomData,
+            },
+        );
+        let _ = manager.fire(
+            state,
+            Event::UpdateUserStats {
+                name: Cow::Borrowed("token_discovery_pool_size"),
+                value: UserStats::new(UserStatsValue::Number(current_corpus_size as u64), AggregatorOps::Avg),
+                phantom: PhantomData,
+            },
+        );
+
         // 3. Add to SmartTokens
         if let Ok(token_meta) = state.metadata_mut::<SmartTokens>() {
             token_meta.add_tokens(&data);
         }
 
+        // 3b. Age out tokens that aren't paying for themselves: decay every
+        // score, prune whatever flatlines outside its probation window. Only
+        // runs when `token_decay_factor` is configured, so the dictionary
+        // keeps growing monotonically (the historical behavior) by default.
+        if let Some(decay_factor) = cfg.token_decay_factor {
+            if let Ok(token_meta) = state.metadata_mut::<SmartTokens>() {
+                let probation = u64::from(cfg.token_probation_uses.unwrap_or(0));
+                token_meta.decay_and_prune(decay_factor, probation);
+            }
+        }
+
+        // 4. Write the learned dictionary back out in .dict format, reusing
+        // `search_interval` via this stage's own gating above.
+        if let Some(path) = &cfg.dict_path {
+            if let Ok(token_meta) = state.metadata::<SmartTokens>() {
+                let dict = crate::dict_format::write_dict(token_meta.tokens());
+                let _ = std::fs::write(path, dict);
+            }
+        }
+
+        // 5. Periodically dump the co-occurrence graph as a .dot file.
+        if let Some(dot_cfg) = &cfg.dot_export {
+            self.dot_calls += 1;
+            if self.dot_calls % dot_cfg.interval.max(1) == 0 {
+                if let Ok(token_meta) = state.metadata::<SmartTokens>() {
+                    let dot = token_meta.to_dot(cfg.displayed_tokens);
+                    let _ = std::fs::write(&dot_cfg.path, dot);
+                }
+            }
+        }
+
+        // 4b. Input-to-state: derive tokens directly from the comparison
+        // operands the target logged during the last execution of the
+        // current testcase, complementing the extractor's coverage-diff
+        // search with a near-free pass over work the target already did.
+        if let Some(cmp_map) = executor.observers().get(&self.cmp_handle) {
+            if let Ok(current_testcase) = state.current_testcase() {
+                if let Some(input) = current_testcase.input().as_ref() {
+                    let input_bytes = input.target_bytes().to_vec();
+                    drop(current_testcase);
+
+                    let discovered = search_tokens_from_cmp(&input_bytes, cmp_map.pairs());
+                    if !discovered.is_empty() {
+                        if !cfg.silent_run {
+                            for (token, offset) in &discovered {
+                                println!(
+                                    "[{}] Found token of length {} at input offset {} via cmp logging",
+                                    STAGE_NAME, token.len(), offset
+                                );
+                            }
+                        }
+                        if let Ok(token_meta) = state.metadata_mut::<SmartTokens>() {
+                            let tokens: Vec<Vec<u8>> = discovered.into_iter().map(|(token, _)| token).collect();
+                            token_meta.add_tokens(&tokens);
+                        }
+                    }
+                }
+            }
+        }
+
+        // 5. Pool with other hosts, if cross-host sync is configured.
+        if let Some(client) = &mut self.token_sync {
+            self.sync_calls += 1;
+            let interval = cfg.token_sync.as_ref().map_or(1, |c| c.sync_interval).max(1);
+            if self.sync_calls % interval == 0 {
+                if let Err(e) = client.publish(data) {
+                    if !cfg.silent_run {
+                        println!("[{STAGE_NAME}] token_sync publish failed: {e}");
+                    }
+                }
+                match client.pull() {
+                    Ok(remote_tokens) if !remote_tokens.is_empty() => {
+                        if let Ok(token_meta) = state.metadata_mut::<SmartTokens>() {
+                            token_meta.add_tokens(&remote_tokens);
+                        }
+                    }
+                    Err(e) if !cfg.silent_run => {
+                        println!("[{STAGE_NAME}] token_sync pull failed: {e}");
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         Ok(())
     }
 }
 
 impl<E, EM, I, S, Z, C, O> TokenDiscoveryStage<E, EM, I, S, Z, C, O> {
-    pub fn new(extractor: Extractor<C>, processors: Vec<Box<dyn Processor>>) -> Self {
+    pub fn new(extractor: Extractor<C>, processors: Vec<Box<dyn Processor>>, cmp_handle: Handle<CmpMapObserver>) -> Self {
+        let token_sync = config()
+            .token_sync
+            .as_ref()
+            .map(|sync_cfg| TokenSyncClient::connect(&sync_cfg.broker_addr, sync_cfg.mode));
+
         Self {
             name: Cow::Borrowed(STAGE_NAME),
             last_corpus_size: 0,
             extractor,
             processors,
             stage_calls: 0,
+            token_sync,
+            sync_calls: 0,
+            dot_calls: 0,
+            cmp_handle,
             phantom: PhantomData,
         }
     }
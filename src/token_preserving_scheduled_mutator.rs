@@ -0,0 +1,394 @@
+use libafl::{
+    inputs::{HasMutatorBytes, ResizableMutator},
+    mutators::{MutationResult, Mutator, MutatorsTuple, ScheduledMutator, ComposedByMutations, MutationId},
+    state::{HasMaxSize, HasRand},
+    corpus::CorpusId,
+    Error,
+    HasMetadata,
+};
+use libafl_bolts::{Named, HasLen, tuples::NamedTuple};
+use std::borrow::Cow;
+use std::num::NonZero;
+use libafl_bolts::rands::Rand;
+
+use crate::tokens::reward_window::RewardWindow;
+
+/// Size of the sliding reward window tracked per token mutation.
+const REWARD_WINDOW_SIZE: usize = 256;
+
+/// A scheduled mutator that preserves token mutations by applying them last
+pub struct TokenPreservingScheduledMutator<MT> {
+    name: Cow<'static, str>,
+    mutations: MT,
+    max_stack_pow: usize,
+    token_indices: Vec<usize>,  // Indices of token mutations in the tuple
+    last_token_used: Option<usize>,  // Track which token mutation was used (raw index)
+    last_token_slot: Option<usize>,  // Index into `reward_windows` for `last_token_used`
+    // Sliding-window median reward tracker per entry in `token_indices`,
+    // used to favor whichever token mutation has been paying off recently
+    // instead of a flat probability and a uniform pick among them.
+    reward_windows: Vec<RewardWindow>,
+}
+
+impl<MT> TokenPreservingScheduledMutator<MT>
+where
+    MT: NamedTuple + HasLen,
+{
+    pub fn new(mutations: MT) -> Self {
+        // Identify which mutations are token mutations at construction time
+        let token_indices = Self::identify_token_mutations(&mutations);
+        let reward_windows = token_indices.iter().map(|_| RewardWindow::new(REWARD_WINDOW_SIZE)).collect();
+
+        Self {
+            name: Cow::from(format!(
+                "TokenPreservingScheduledMutator[{}]",
+                mutations.names().join(", ")
+            )),
+            mutations,
+            max_stack_pow: 7,
+            token_indices,
+            last_token_used: None,
+            last_token_slot: None,
+            reward_windows,
+        }
+    }
+
+    /// Identify token mutations by their name
+    fn identify_token_mutations(mutations: &MT) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for (i, name) in mutations.names().iter().enumerate() {
+            // Check for both SmartToken and regular Token mutations
+            if name.contains("Token") {
+                indices.push(i);
+            }
+        }
+        indices
+    }
+}
+
+impl<MT> TokenPreservingScheduledMutator<MT>
+where
+    MT: HasLen,
+{
+    fn is_token_mutation(&self, idx: usize) -> bool {
+        self.token_indices.contains(&idx)
+    }
+
+    /// Schedule a non-token mutation
+    fn schedule_non_token<S: HasRand>(&self, state: &mut S) -> MutationId {
+        let total_len = self.mutations.len();
+        let non_token_count = total_len - self.token_indices.len();
+        if non_token_count == 0 {
+            // Only token mutations available
+            return self.schedule_token(state).into();
+        }
+
+        loop {
+            let idx = state.rand_mut().below(unsafe { NonZero::new(total_len).unwrap_unchecked() });
+            if !self.is_token_mutation(idx) {
+                return idx.into();
+            }
+        }
+    }
+
+    /// Schedule a token mutation, weighted by how far each token's recent
+    /// reward sits above the sliding-window median. Returns the slot (index
+    /// into `token_indices`/`reward_windows`) and the raw mutation index.
+    fn schedule_token<S: HasRand>(&self, state: &mut S) -> (usize, usize) {
+        if self.token_indices.is_empty() {
+            panic!("No token mutations available");
+        }
+
+        let medians: Vec<f64> = self.reward_windows.iter().map(RewardWindow::median).collect();
+        let weights: Vec<f64> = self
+            .reward_windows
+            .iter()
+            .zip(&medians)
+            .map(|(w, med)| (w.last() - med).max(0.0))
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        let slot = if total > 0.0 {
+            let mut pick = state.rand_mut().next() as f64 / u64::MAX as f64 * total;
+            let mut chosen = weights.len() - 1;
+            for (i, w) in weights.iter().enumerate() {
+                if pick < *w {
+                    chosen = i;
+                    break;
+                }
+                pick -= w;
+            }
+            chosen
+        } else {
+            state.rand_mut().below(unsafe { NonZero::new(self.token_indices.len()).unwrap_unchecked() })
+        };
+
+        (slot, self.token_indices[slot])
+    }
+
+    /// Adaptive replacement for the old flat 30% chance of applying a token
+    /// mutation: the more the best-performing token's recent reward clears
+    /// the average dispersion across all token windows, the more we lean on
+    /// exploiting it.
+    fn use_token_probability(&self) -> f64 {
+        if self.reward_windows.is_empty() {
+            return 0.0;
+        }
+
+        let best_excess = self
+            .reward_windows
+            .iter()
+            .map(|w| (w.last() - w.median()).max(0.0))
+            .fold(0.0_f64, f64::max);
+
+        let avg_dispersion = self.reward_windows.iter().map(RewardWindow::dispersion).sum::<f64>()
+            / self.reward_windows.len() as f64;
+
+        // Exploration floor keeps token mutations from starving out entirely
+        // while the windows are still warming up.
+        (0.1 + best_excess / (avg_dispersion + 1.0)).min(1.0)
+    }
+}
+
+impl<MT> Named for TokenPreservingScheduledMutator<MT> {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl<MT> ComposedByMutations for TokenPreservingScheduledMutator<MT> {
+    type Mutations = MT;
+
+    fn mutations(&self) -> &MT {
+        &self.mutations
+    }
+
+    fn mutations_mut(&mut self) -> &mut MT {
+        &mut self.mutations
+    }
+}
+
+impl<I, MT, S> Mutator<I, S> for TokenPreservingScheduledMutator<MT>
+where
+    MT: MutatorsTuple<I, S> + HasLen,
+    S: HasRand,
+{
+    fn mutate(&mut self, state: &mut S, input: &mut I) -> Result<MutationResult, Error> {
+        let mut r = MutationResult::Skipped;
+        let base_iterations = self.iterations(state, input);
+        self.last_token_used = None;
+        self.last_token_slot = None;
+
+        // Decide upfront if we'll use a token mutation, weighted by how well
+        // token mutations have been paying off recently rather than a flat
+        // 30% chance.
+        let use_token = !self.token_indices.is_empty() && {
+            let threshold = (self.use_token_probability() * 1000.0) as u64;
+            state.rand_mut().below(NonZero::new(1000).unwrap()) < threshold
+        };
+
+        let iterations = if use_token {
+            // If using token, apply fewer stacked mutations to preserve it
+            (base_iterations / 2).max(1)
+        } else {
+            base_iterations
+        };
+
+        // Apply non-token mutations
+        for _ in 0..iterations {
+            let idx = if use_token {
+                self.schedule_non_token(state)
+            } else {
+                self.schedule(state, input)  // Use regular scheduling when no token
+            };
+
+            let outcome = self.mutations_mut().get_and_mutate(idx, state, input)?;
+            if outcome == MutationResult::Mutated {
+                r = MutationResult::Mutated;
+            }
+        }
+
+        // Apply token mutation last (if we decided to use one)
+        if use_token {
+            let (slot, token_idx) = self.schedule_token(state);
+            let outcome = self.mutations_mut().get_and_mutate(token_idx.into(), state, input)?;
+            if outcome == MutationResult::Mutated {
+                r = MutationResult::Mutated;
+                self.last_token_used = Some(token_idx);
+                self.last_token_slot = Some(slot);
+            }
+        }
+
+        Ok(r)
+    }
+
+    fn post_exec(&mut self, state: &mut S, corpus_id: Option<CorpusId>) -> Result<(), Error> {
+        // Only call post_exec if we used a token mutation
+        if let Some(idx) = self.last_token_used {
+            self.mutations_mut().get_and_post_exec(idx, state, corpus_id)?;
+            // A new corpus entry is this mutation's success signal; feed it
+            // into the token's sliding reward window.
+            if let Some(slot) = self.last_token_slot {
+                let reward = if corpus_id.is_some() { 1.0 } else { 0.0 };
+                self.reward_windows[slot].push(reward);
+            }
+            self.last_token_used = None;
+            self.last_token_slot = None;
+        }
+        Ok(())
+    }
+}
+
+impl<I, MT, S> ScheduledMutator<I, S> for TokenPreservingScheduledMutator<MT>
+where
+    MT: MutatorsTuple<I, S> + HasLen,
+    S: HasRand,
+{
+    fn iterations(&self, state: &mut S, _: &I) -> u64 {
+        1 << (1 + state.rand_mut().below_or_zero(self.max_stack_pow))
+    }
+
+    fn schedule(&self, state: &mut S, _: &I) -> MutationId {
+        debug_assert_ne!(self.mutations.len(), 0);
+        state
+            .rand_mut()
+            .below(unsafe { NonZero::new(self.mutations.len()).unwrap_unchecked() })
+            .into()
+    }
+}
+
+/// A token with one or more variable "hole" regions between literal runs,
+/// e.g. `"Content-Length: " · ? · "\r\n"`. `hole_bounds[k]` is the
+/// `(min_width, max_width)` a reader should accept for the hole between
+/// `literals[k]` and `literals[k + 1]`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GappedToken {
+    pub literals: Vec<Vec<u8>>,
+    pub hole_bounds: Vec<(usize, usize)>,
+}
+
+/// Holds `GappedToken`s for [`GappedTokenSplice`] to splice in. Entries only
+/// arrive through `add_token`; token discovery does not produce gapped
+/// tokens or add any here.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct GappedTokens {
+    tokens: Vec<GappedToken>,
+}
+
+libafl_bolts::impl_serdeany!(GappedTokens);
+
+impl GappedTokens {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_token(&mut self, token: GappedToken) {
+        self.tokens.push(token);
+    }
+
+    #[inline]
+    pub fn tokens(&self) -> &[GappedToken] {
+        &self.tokens
+    }
+}
+
+/// Splices a [`GappedToken`] into the input, filling each hole with either a
+/// corpus-derived byte run (taken from the input itself) or random bytes.
+#[derive(Debug, Default)]
+pub struct GappedTokenSplice;
+
+impl GappedTokenSplice {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Renders a gapped token to concrete bytes. Each hole is filled, on a
+    /// coin flip, with a run of bytes from the surrounding input (only when
+    /// the input is at least as long as the hole) or else with random bytes.
+    fn instantiate<S: HasRand>(token: &GappedToken, input: &[u8], state: &mut S) -> Vec<u8> {
+        let mut out = Vec::new();
+        for (i, literal) in token.literals.iter().enumerate() {
+            out.extend_from_slice(literal);
+            if let Some(&(min_w, max_w)) = token.hole_bounds.get(i) {
+                let width = if max_w > min_w {
+                    min_w + state.rand_mut().below(unsafe { NonZero::new(max_w - min_w + 1).unwrap_unchecked() })
+                } else {
+                    min_w
+                };
+
+                let from_corpus = width > 0 && input.len() >= width && state.rand_mut().coinflip(0.5);
+                if from_corpus {
+                    let off = state.rand_mut().below(unsafe { NonZero::new(input.len() - width + 1).unwrap_unchecked() });
+                    out.extend_from_slice(&input[off..off + width]);
+                } else {
+                    for _ in 0..width {
+                        out.push(state.rand_mut().next() as u8);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+impl<I, S> Mutator<I, S> for GappedTokenSplice
+where
+    S: HasMetadata + HasRand + HasMaxSize,
+    I: ResizableMutator<u8> + HasMutatorBytes,
+{
+    fn mutate(&mut self, state: &mut S, input: &mut I) -> Result<MutationResult, Error> {
+        let max_size = state.max_size();
+
+        let token_idx = {
+            let Some(meta) = state.metadata_map().get::<GappedTokens>() else {
+                return Ok(MutationResult::Skipped);
+            };
+            let Some(count) = NonZero::new(meta.tokens().len()) else {
+                return Ok(MutationResult::Skipped);
+            };
+            state.rand_mut().below(count)
+        };
+
+        let current_bytes = input.mutator_bytes().to_vec();
+        // Clone the chosen token out of `state`'s metadata map before
+        // borrowing `state` mutably in `instantiate` -- `meta`/`token` would
+        // otherwise still hold an immutable borrow of `state` alive across
+        // that call.
+        let token = {
+            let meta = state.metadata_map().get::<GappedTokens>().unwrap();
+            meta.tokens()[token_idx].clone()
+        };
+        let rendered = Self::instantiate(&token, &current_bytes, state);
+
+        let size = current_bytes.len();
+        let mut len = rendered.len();
+        if size + len > max_size {
+            if max_size > size {
+                len = max_size - size;
+            } else {
+                return Ok(MutationResult::Skipped);
+            }
+        }
+
+        let Some(size_nz) = NonZero::new(size.saturating_add(1)) else {
+            return Ok(MutationResult::Skipped);
+        };
+        let off = state.rand_mut().below(size_nz);
+
+        input.resize(size + len, 0);
+        let bytes = input.mutator_bytes_mut();
+        bytes.copy_within(off..size, off + len);
+        bytes[off..off + len].copy_from_slice(&rendered[..len]);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl Named for GappedTokenSplice {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("GappedTokenSplice");
+        &NAME
+    }
+}
\ No newline at end of file
@@ -0,0 +1,3 @@
+pub mod network_token_sync;
+pub mod reward_window;
+pub mod shared_token_storage;
@@ -0,0 +1,213 @@
+// src/tokens/network_token_sync.rs
+//
+// Cross-host counterpart to `SharedTokenStorage`: instead of a POSIX shmem
+// seqlock shared by processes on one machine, nodes publish their newly
+// discovered tokens to a broker over TCP and pull back the deduplicated
+// union, using the same "only merge what's newer than `last_sequence`"
+// discipline as `SharedTokenStorage::read_tokens`.
+
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+/// How a node pushes newly discovered tokens to the broker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Send the batch and block until the broker acks the merged sequence
+    /// number, so the caller knows its tokens are visible to other nodes
+    /// before it resumes fuzzing.
+    Blocking,
+    /// Push the batch and keep fuzzing without waiting for a reply.
+    FireAndForget,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Message {
+    Publish { tokens: Vec<Vec<u8>> },
+    Ack { sequence: u32 },
+    Pull { since: u32 },
+    Delta { sequence: u32, tokens: Vec<Vec<u8>> },
+}
+
+fn write_message(stream: &mut TcpStream, msg: &Message) -> io::Result<()> {
+    let bytes = serde_json::to_vec(msg).map_err(io::Error::other)?;
+    stream.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    stream.write_all(&bytes)
+}
+
+fn read_message(stream: &mut TcpStream) -> io::Result<Message> {
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes)?;
+    let len = u32::from_le_bytes(len_bytes) as usize;
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf)?;
+    serde_json::from_slice(&buf).map_err(io::Error::other)
+}
+
+/// Deduplicated, sequence-stamped token set shared by every connected node.
+/// Each kept token remembers the sequence number it was merged at, so
+/// `delta_since` can return only what's actually new to a given caller
+/// instead of the whole set.
+struct BrokerState {
+    sequence: u32,
+    tokens: Vec<(u32, Vec<u8>)>,
+    seen: HashSet<Vec<u8>>,
+    max_tokens: usize,
+    max_token_length: usize,
+}
+
+impl BrokerState {
+    fn merge(&mut self, incoming: Vec<Vec<u8>>) -> u32 {
+        let mut fresh = Vec::new();
+        for token in incoming {
+            if token.len() > self.max_token_length || self.seen.len() >= self.max_tokens {
+                continue;
+            }
+            if self.seen.insert(token.clone()) {
+                fresh.push(token);
+            }
+        }
+        if !fresh.is_empty() {
+            self.sequence += 1;
+            let sequence = self.sequence;
+            self.tokens.extend(fresh.into_iter().map(|token| (sequence, token)));
+        }
+        self.sequence
+    }
+
+    /// Only the tokens merged at a sequence number strictly newer than
+    /// `since` -- mirrors `SharedTokenStorage::read_tokens`'s "only merge
+    /// what's newer than `last_sequence`" discipline instead of resending
+    /// the whole deduplicated set on every pull.
+    fn delta_since(&self, since: u32) -> (u32, Vec<Vec<u8>>) {
+        let tokens = self
+            .tokens
+            .iter()
+            .filter(|(seq, _)| *seq > since)
+            .map(|(_, token)| token.clone())
+            .collect();
+        (self.sequence, tokens)
+    }
+}
+
+/// Listens on `bind_addr` and pools `Publish`/`Pull` requests from every
+/// connected node, mirroring `SharedTokenStorage`'s even/odd sequence guard
+/// with a plain monotonically increasing counter (TCP framing already gives
+/// us atomic, ordered message delivery, so no seqlock is needed on this
+/// side).
+pub struct TokenSyncBroker {
+    listener: TcpListener,
+    state: Arc<Mutex<BrokerState>>,
+}
+
+impl TokenSyncBroker {
+    pub fn bind(bind_addr: &str, max_tokens: usize, max_token_length: usize) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(bind_addr)?,
+            state: Arc::new(Mutex::new(BrokerState {
+                sequence: 0,
+                tokens: Vec::new(),
+                seen: HashSet::new(),
+                max_tokens,
+                max_token_length,
+            })),
+        })
+    }
+
+    /// Runs the broker loop forever, spawning one thread per connected node.
+    pub fn serve(self) -> io::Result<()> {
+        for stream in self.listener.incoming() {
+            let mut stream = stream?;
+            let state = Arc::clone(&self.state);
+            std::thread::spawn(move || {
+                let _ = Self::handle_client(&mut stream, &state);
+            });
+        }
+        Ok(())
+    }
+
+    fn handle_client(stream: &mut TcpStream, state: &Arc<Mutex<BrokerState>>) -> io::Result<()> {
+        loop {
+            let msg = match read_message(stream) {
+                Ok(msg) => msg,
+                Err(_) => return Ok(()), // peer closed the connection
+            };
+
+            match msg {
+                Message::Publish { tokens } => {
+                    let sequence = state.lock().unwrap().merge(tokens);
+                    write_message(stream, &Message::Ack { sequence })?;
+                }
+                Message::Pull { since } => {
+                    let (sequence, tokens) = state.lock().unwrap().delta_since(since);
+                    write_message(stream, &Message::Delta { sequence, tokens })?;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Client-side handle used by a single fuzzer node to publish newly
+/// discovered tokens and pull the broker's merged set, tracking
+/// `last_sequence` exactly like `SharedTokenStorage::read_tokens` does for
+/// the shmem case.
+pub struct TokenSyncClient {
+    broker_addr: String,
+    mode: SyncMode,
+    last_sequence: u32,
+}
+
+impl TokenSyncClient {
+    pub fn connect(broker_addr: &str, mode: SyncMode) -> Self {
+        Self {
+            broker_addr: broker_addr.to_string(),
+            mode,
+            last_sequence: 0,
+        }
+    }
+
+    /// Publishes `tokens` to the broker. In [`SyncMode::Blocking`] this waits
+    /// for the broker's ack before returning; in [`SyncMode::FireAndForget`]
+    /// it sends the batch and returns immediately without reading a reply.
+    pub fn publish(&mut self, tokens: Vec<Vec<u8>>) -> io::Result<()> {
+        if tokens.is_empty() {
+            return Ok(());
+        }
+
+        let mut stream = TcpStream::connect(&self.broker_addr)?;
+        write_message(&mut stream, &Message::Publish { tokens })?;
+
+        if self.mode == SyncMode::Blocking {
+            // Wait for the ack only; `last_sequence` stays where the last pull
+            // left it, since the acked sequence also covers tokens other nodes
+            // published in between, which the next `pull` must still fetch.
+            read_message(&mut stream)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pulls every token newer than `last_sequence`, merging them like
+    /// `SharedTokenStorage::read_tokens`: only entries published after the
+    /// last successful pull are returned.
+    pub fn pull(&mut self) -> io::Result<Vec<Vec<u8>>> {
+        let mut stream = TcpStream::connect(&self.broker_addr)?;
+        write_message(&mut stream, &Message::Pull { since: self.last_sequence })?;
+
+        match read_message(&mut stream)? {
+            Message::Delta { sequence, tokens } => {
+                if sequence == self.last_sequence {
+                    return Ok(Vec::new());
+                }
+                self.last_sequence = sequence;
+                Ok(tokens)
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+}
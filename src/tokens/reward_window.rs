@@ -0,0 +1,193 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OrderedF64(f64);
+
+impl Eq for OrderedF64 {}
+
+impl PartialOrd for OrderedF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedF64 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Running median (and dispersion) of the last `capacity` reward samples,
+/// via the two-heap trick: a max-heap `left` of the lower half and a
+/// min-heap `right` of the upper half, kept balanced so `left` is never
+/// more than one element ahead of `right`. The value sliding out of the
+/// window is removed lazily through `left_removed`/`right_removed`
+/// shadow heaps so eviction stays O(log n).
+#[derive(Debug, Clone)]
+pub struct RewardWindow {
+    capacity: usize,
+    window: VecDeque<f64>,
+
+    left: BinaryHeap<OrderedF64>,
+    right: BinaryHeap<Reverse<OrderedF64>>,
+    left_removed: BinaryHeap<OrderedF64>,
+    right_removed: BinaryHeap<Reverse<OrderedF64>>,
+
+    left_count: usize,
+    right_count: usize,
+    left_sum: f64,
+    right_sum: f64,
+}
+
+impl RewardWindow {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            window: VecDeque::with_capacity(capacity),
+            left: BinaryHeap::new(),
+            right: BinaryHeap::new(),
+            left_removed: BinaryHeap::new(),
+            right_removed: BinaryHeap::new(),
+            left_count: 0,
+            right_count: 0,
+            left_sum: 0.0,
+            right_sum: 0.0,
+        }
+    }
+
+    /// Records a new reward sample, evicting the oldest sample once the
+    /// window is full.
+    pub fn push(&mut self, reward: f64) {
+        if self.window.len() == self.capacity {
+            if let Some(oldest) = self.window.pop_front() {
+                self.remove(oldest);
+            }
+        }
+        self.window.push_back(reward);
+        self.insert(reward);
+    }
+
+    fn insert(&mut self, x: f64) {
+        let goes_left = match self.left.peek() {
+            Some(top) => x <= top.0,
+            None => true,
+        };
+
+        if goes_left {
+            self.left.push(OrderedF64(x));
+            self.left_count += 1;
+            self.left_sum += x;
+        } else {
+            self.right.push(Reverse(OrderedF64(x)));
+            self.right_count += 1;
+            self.right_sum += x;
+        }
+        self.rebalance();
+    }
+
+    fn remove(&mut self, x: f64) {
+        let on_left = match self.left.peek() {
+            Some(top) => x <= top.0,
+            None => false,
+        };
+
+        if on_left {
+            self.left_removed.push(OrderedF64(x));
+            self.left_count = self.left_count.saturating_sub(1);
+            self.left_sum -= x;
+        } else {
+            self.right_removed.push(Reverse(OrderedF64(x)));
+            self.right_count = self.right_count.saturating_sub(1);
+            self.right_sum -= x;
+        }
+        self.rebalance();
+    }
+
+    /// Drops any heap tops that a prior `remove` already marked as gone.
+    fn prune(&mut self) {
+        while let (Some(top), Some(rem)) = (self.left.peek(), self.left_removed.peek()) {
+            if top.0 == rem.0 {
+                self.left.pop();
+                self.left_removed.pop();
+            } else {
+                break;
+            }
+        }
+        while let (Some(Reverse(top)), Some(Reverse(rem))) = (self.right.peek(), self.right_removed.peek()) {
+            if top.0 == rem.0 {
+                self.right.pop();
+                self.right_removed.pop();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn rebalance(&mut self) {
+        self.prune();
+
+        if self.left_count > self.right_count + 1 {
+            if let Some(top) = self.left.pop().map(|v| v.0) {
+                self.left_sum -= top;
+                self.left_count -= 1;
+                self.right.push(Reverse(OrderedF64(top)));
+                self.right_count += 1;
+                self.right_sum += top;
+            }
+        } else if self.right_count > self.left_count {
+            if let Some(top) = self.right.pop().map(|Reverse(v)| v.0) {
+                self.right_sum -= top;
+                self.right_count -= 1;
+                self.left.push(OrderedF64(top));
+                self.left_count += 1;
+                self.left_sum += top;
+            }
+        }
+
+        self.prune();
+    }
+
+    /// The running median of the current window, `0.0` when empty.
+    #[must_use]
+    pub fn median(&self) -> f64 {
+        if self.left_count == 0 {
+            return 0.0;
+        }
+        if self.left_count == self.right_count {
+            let l = self.left.peek().map_or(0.0, |v| v.0);
+            let r = self.right.peek().map_or(l, |Reverse(v)| v.0);
+            (l + r) / 2.0
+        } else {
+            self.left.peek().map_or(0.0, |v| v.0)
+        }
+    }
+
+    /// Sum of absolute deviations from the median, a cheap dispersion
+    /// measure derived from the running sums/counts without re-scanning
+    /// the window.
+    #[must_use]
+    pub fn dispersion(&self) -> f64 {
+        let med = self.median();
+        med * self.left_count as f64 - self.left_sum + self.right_sum - med * self.right_count as f64
+    }
+
+    /// The most recently pushed reward, or the median if the window is
+    /// still empty.
+    #[must_use]
+    pub fn last(&self) -> f64 {
+        self.window.back().copied().unwrap_or_else(|| self.median())
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.left_count + self.right_count
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
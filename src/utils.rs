@@ -3,4 +3,64 @@ macro_rules! print_stats {
     ($name:expr, $($arg:tt)*) => {
         println!("{:<20} {}", format!("[{}]", $name.to_uppercase()), format!($($arg)*))
     };
+}
+
+use crate::config::CoverageComparisonMode;
+
+/// AFL-style log-scale hit-count bucketing: collapses a raw edge hit count
+/// down to one of 9 buckets (0, 1, 2, 3, 4-7, 8-15, 16-31, 32-127, 128+) so
+/// that loop-iteration-count jitter between otherwise-identical runs maps
+/// to the same bucket instead of registering as a coverage difference.
+#[must_use]
+pub fn classify_count(count: u64) -> u64 {
+    match count {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        3 => 3,
+        4..=7 => 4,
+        8..=15 => 5,
+        16..=31 => 6,
+        32..=127 => 7,
+        _ => 8,
+    }
+}
+
+/// Maps `classify_count` over every entry of a raw coverage map.
+#[must_use]
+pub fn classify_counts(map: &[u64]) -> Vec<u64> {
+    map.iter().copied().map(classify_count).collect()
+}
+
+/// Compares two coverage maps of equal length under `mode`: `Exact` compares
+/// raw hit counts, `Bucketed` compares `classify_count` buckets, and
+/// `TouchedEdges` compares only whether each edge was hit at all.
+#[must_use]
+pub fn coverage_matches(a: &[u64], b: &[u64], mode: CoverageComparisonMode) -> bool {
+    match mode {
+        CoverageComparisonMode::Exact => a == b,
+        CoverageComparisonMode::Bucketed => {
+            a.len() == b.len() && a.iter().zip(b).all(|(&x, &y)| classify_count(x) == classify_count(y))
+        }
+        CoverageComparisonMode::TouchedEdges => {
+            a.len() == b.len() && a.iter().zip(b).all(|(&x, &y)| (x > 0) == (y > 0))
+        }
+    }
+}
+
+/// Intersects a set of same-length coverage maps from repeated executions of
+/// the same input, taking the minimum hit count per edge across all runs.
+/// Used to stabilize a coverage map against flaky edges before it's used as
+/// a comparison baseline or candidate: an edge that only fires on some runs
+/// settles to its lowest (and, for `TouchedEdges`, "not hit") observed count.
+#[must_use]
+pub fn intersect_coverage(maps: &[Vec<u64>]) -> Vec<u64> {
+    let Some(first) = maps.first() else { return Vec::new(); };
+    let mut result = first.clone();
+    for map in &maps[1..] {
+        for (r, &m) in result.iter_mut().zip(map) {
+            *r = (*r).min(m);
+        }
+    }
+    result
 }
\ No newline at end of file